@@ -0,0 +1,426 @@
+use serde::Serialize;
+use serde_json::json;
+use std::convert::TryFrom;
+use std::fmt::Debug;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use structopt::StructOpt;
+
+use instagram::web_api::behaviour::{
+    FetchMediaComments, FetchMediaInfos, FetchUserFeed, FetchUserInfos,
+};
+use instagram::web_api::domain::{MediaId, Shortcode};
+use instagram::web_api::download::{Downloader, Size};
+use instagram::web_api::options::{FetchMediaCommentsOptions, FetchUserFeedOptions};
+use instagram::web_api::reference::Reference;
+use instagram::web_api::{AuthenticatedClient, Client, Credentials, Session};
+
+/// Medias requested per page of a feed
+const FEED_PAGE_SIZE: usize = 50;
+
+/// Comments requested per page of a media's comments
+const COMMENTS_PAGE_SIZE: usize = 50;
+
+#[derive(Debug, Clone, Copy)]
+enum Format {
+    Json,
+    Ndjson,
+}
+
+impl FromStr for Format {
+    type Err = String;
+
+    fn from_str(format: &str) -> Result<Self, Self::Err> {
+        match format {
+            "json" => Ok(Self::Json),
+            "ndjson" => Ok(Self::Ndjson),
+            _ => Err(format!("unknown format `{format}`, use json or ndjson")),
+        }
+    }
+}
+
+/// Pull data from Instagram as JSON
+///
+/// Commands run logged in once a session was saved by `instagram login`.
+#[derive(Debug, StructOpt)]
+#[structopt(name = "instagram")]
+struct Cli {
+    /// Output format, `json` or `ndjson` for one document per line
+    #[structopt(long, default_value = "json")]
+    format: Format,
+
+    /// File holding the session saved by `login`
+    #[structopt(long, default_value = "instagram-session.json", parse(from_os_str))]
+    session: PathBuf,
+
+    #[structopt(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, StructOpt)]
+enum Command {
+    /// Fetch the profile of a user, from a username or a profile url
+    User { user: String },
+    /// Fetch the medias of a user, newest first
+    Feed {
+        user: String,
+        /// Number of medias to fetch
+        #[structopt(long, default_value = "12")]
+        limit: usize,
+    },
+    /// Fetch a media, from a shortcode, a media id or a post url
+    Media { media: String },
+    /// Fetch the comments of a media
+    Comments {
+        media: String,
+        /// Number of comments to fetch
+        #[structopt(long, default_value = "50")]
+        limit: usize,
+    },
+    /// Download the images and videos of a media
+    Download {
+        media: String,
+        /// Directory receiving the files
+        #[structopt(long, default_value = ".", parse(from_os_str))]
+        dir: PathBuf,
+        /// `best`, `smallest` or the minimum width of the files
+        #[structopt(long, default_value = "best", parse(try_from_str = parse_size))]
+        size: Size,
+        /// Download the cover image of videos instead of the videos
+        #[structopt(long)]
+        no_videos: bool,
+    },
+    /// Log in and save the session, the password is read from `INSTAGRAM_PASSWORD` when omitted
+    Login {
+        username: String,
+        #[structopt(long)]
+        password: Option<String>,
+    },
+    /// Show the saved session, without its secrets
+    Session {
+        /// Delete the saved session
+        #[structopt(long)]
+        clear: bool,
+    },
+}
+
+fn parse_size(size: &str) -> Result<Size, String> {
+    match size {
+        "best" => Ok(Size::Best),
+        "smallest" => Ok(Size::Smallest),
+        width => width
+            .parse()
+            .map(Size::AtLeast)
+            .map_err(|_| format!("invalid size `{width}`")),
+    }
+}
+
+fn failed<E: Debug>(error: E) -> String {
+    format!("{error:?}")
+}
+
+fn parse_username(user: &str) -> Result<String, String> {
+    if !user.contains("instagram.com") {
+        return Ok(user.trim_start_matches('@').to_string());
+    }
+
+    match Reference::parse(user).map_err(failed)? {
+        Reference::User(username) => Ok(username),
+        _ => Err(format!("`{user}` isn't a profile url")),
+    }
+}
+
+fn parse_media(media: &str) -> Result<MediaId, String> {
+    if media.contains("instagram.com") {
+        return match Reference::parse(media).map_err(failed)? {
+            Reference::Post(shortcode) | Reference::Reel(shortcode) | Reference::Tv(shortcode) => {
+                shortcode.to_media_id().map_err(failed)
+            }
+            _ => Err(format!("`{media}` isn't a post url")),
+        };
+    }
+
+    if media.bytes().all(|c| c.is_ascii_digit()) {
+        return media.parse().map_err(failed);
+    }
+
+    media
+        .parse::<Shortcode>()
+        .and_then(|shortcode| shortcode.to_media_id())
+        .map_err(failed)
+}
+
+/// Print documents in the chosen format, a list is printed as an array in JSON
+struct Output {
+    format: Format,
+    items: Vec<serde_json::Value>,
+}
+
+impl Output {
+    const fn new(format: Format) -> Self {
+        Self {
+            format,
+            items: Vec::new(),
+        }
+    }
+
+    fn print<T: Serialize>(&self, value: &T) -> Result<(), String> {
+        let json = match self.format {
+            Format::Json => serde_json::to_string_pretty(value),
+            Format::Ndjson => serde_json::to_string(value),
+        }
+        .map_err(failed)?;
+
+        println!("{json}");
+
+        Ok(())
+    }
+
+    fn push<T: Serialize>(&mut self, item: &T) -> Result<(), String> {
+        match self.format {
+            Format::Json => {
+                self.items.push(serde_json::to_value(item).map_err(failed)?);
+
+                Ok(())
+            }
+            Format::Ndjson => self.print(item),
+        }
+    }
+
+    fn finish(self) -> Result<(), String> {
+        match self.format {
+            Format::Json => self.print(&self.items),
+            Format::Ndjson => Ok(()),
+        }
+    }
+}
+
+fn read_session(path: &Path) -> Result<Option<Session>, String> {
+    match std::fs::read(path) {
+        Ok(session) => serde_json::from_slice(&session)
+            .map(Some)
+            .map_err(|error| format!("invalid session in {}: {}", path.display(), error)),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(None),
+        Err(error) => Err(format!("unable to read {}: {}", path.display(), error)),
+    }
+}
+
+fn write_session(path: &Path, session: &Session) -> Result<(), String> {
+    use std::io::Write;
+
+    let mut options = std::fs::OpenOptions::new();
+
+    options.write(true).create(true).truncate(true);
+
+    // The cookies give access to the account
+    #[cfg(unix)]
+    std::os::unix::fs::OpenOptionsExt::mode(&mut options, 0o600);
+
+    let json = serde_json::to_vec_pretty(session).map_err(failed)?;
+
+    options
+        .open(path)
+        .and_then(|mut file| file.write_all(&json))
+        .map_err(|error| format!("unable to write {}: {}", path.display(), error))
+}
+
+impl Cli {
+    async fn run(self) -> Result<(), String> {
+        let output = Output::new(self.format);
+
+        match self.command {
+            Command::Login { username, password } => {
+                let password = password
+                    .or_else(|| std::env::var("INSTAGRAM_PASSWORD").ok())
+                    .ok_or("missing password, use --password or INSTAGRAM_PASSWORD")?;
+
+                let credentials = Credentials {
+                    username: &username,
+                    password: &password,
+                };
+                let session = Client::new()
+                    .login(&credentials)
+                    .await
+                    .map_err(failed)?
+                    .session();
+
+                write_session(&self.session, &session)?;
+
+                output.print(&json!({
+                    "session": self.session,
+                    "user_id": session.user_id(),
+                }))
+            }
+            Command::Session { clear: true } => {
+                std::fs::remove_file(&self.session).map_err(|error| {
+                    format!("unable to delete {}: {}", self.session.display(), error)
+                })?;
+
+                output.print(&json!({ "session": self.session, "cleared": true }))
+            }
+            Command::Session { clear: false } => {
+                let session = read_session(&self.session)?.ok_or_else(|| {
+                    format!(
+                        "no session in {}, run `instagram login` first",
+                        self.session.display()
+                    )
+                })?;
+                let cookies: Vec<&str> = session
+                    .cookies
+                    .iter()
+                    .map(|(name, _)| name.as_str())
+                    .collect();
+
+                output.print(&json!({
+                    "session": self.session,
+                    "user_id": session.user_id(),
+                    "cookies": cookies,
+                }))
+            }
+            command => match read_session(&self.session)? {
+                Some(session) => {
+                    let client = AuthenticatedClient::from_session(Client::new(), session);
+
+                    run(&client, client.downloader(), command, output).await
+                }
+                None => {
+                    let client = Client::new();
+
+                    run(&client, client.downloader(), command, output).await
+                }
+            },
+        }
+    }
+}
+
+async fn run<C>(
+    client: &C,
+    downloader: Downloader,
+    command: Command,
+    mut output: Output,
+) -> Result<(), String>
+where
+    C: FetchUserInfos + FetchUserFeed + FetchMediaInfos + FetchMediaComments + Sync,
+{
+    match command {
+        Command::User { user } => {
+            let username = parse_username(&user)?;
+            let user = client.fetch_user_infos(&username).await.map_err(failed)?;
+
+            output.print(&user)
+        }
+        Command::Feed { user, limit } => {
+            let username = parse_username(&user)?;
+            let user_id = client.fetch_user_infos(&username).await.map_err(failed)?.id;
+            let mut cursor: Option<String> = None;
+            let mut remaining = limit;
+
+            while remaining > 0 {
+                let count = i16::try_from(remaining.min(FEED_PAGE_SIZE)).unwrap_or(12);
+                let mut options = FetchUserFeedOptions::default().set_count(count);
+
+                if let Some(ref after) = cursor {
+                    options = options.set_after(after);
+                }
+
+                let page = client
+                    .fetch_user_feed(user_id, Some(options))
+                    .await
+                    .map_err(failed)?;
+
+                for media in page.medias.iter().take(remaining) {
+                    output.push(media)?;
+                }
+
+                remaining = remaining.saturating_sub(page.medias.len());
+
+                match page.pagination_infos.end_cursor() {
+                    Some(end_cursor) if page.pagination_infos.has_next_page() => {
+                        cursor = Some(end_cursor.to_string());
+                    }
+                    _ => break,
+                }
+            }
+
+            output.finish()
+        }
+        Command::Media { media } => {
+            let media = client
+                .fetch_media_infos(parse_media(&media)?)
+                .await
+                .map_err(failed)?;
+
+            output.print(&media)
+        }
+        Command::Comments { media, limit } => {
+            let media_id = parse_media(&media)?;
+            let mut cursor: Option<String> = None;
+            let mut remaining = limit;
+
+            while remaining > 0 {
+                let count = i16::try_from(remaining.min(COMMENTS_PAGE_SIZE)).unwrap_or(12);
+                let mut options = FetchMediaCommentsOptions::default().set_count(count);
+
+                if let Some(ref after) = cursor {
+                    options = options.set_after(after);
+                }
+
+                let page = client
+                    .fetch_media_comments(media_id, Some(options))
+                    .await
+                    .map_err(failed)?;
+
+                for comment in page.comments().iter().take(remaining) {
+                    output.push(comment)?;
+                }
+
+                remaining = remaining.saturating_sub(page.comments().len());
+
+                match page.pagination_infos().end_cursor() {
+                    Some(end_cursor) if page.pagination_infos().has_next_page() => {
+                        cursor = Some(end_cursor.to_string());
+                    }
+                    _ => break,
+                }
+            }
+
+            output.finish()
+        }
+        Command::Download {
+            media,
+            dir,
+            size,
+            no_videos,
+        } => {
+            let media = client
+                .fetch_media_infos(parse_media(&media)?)
+                .await
+                .map_err(failed)?;
+
+            let mut downloader = downloader.set_size(size);
+
+            if no_videos {
+                downloader = downloader.without_videos();
+            }
+
+            for download in downloader
+                .download_to_dir(&media, &dir)
+                .await
+                .map_err(failed)?
+            {
+                output.push(&download)?;
+            }
+
+            output.finish()
+        }
+        Command::Login { .. } | Command::Session { .. } => Ok(()),
+    }
+}
+
+#[tokio::main]
+async fn main() {
+    if let Err(error) = Cli::from_args().run().await {
+        eprintln!("instagram: {error}");
+        std::process::exit(1);
+    }
+}
@@ -0,0 +1,463 @@
+use serde::{Deserialize, Serialize};
+use std::collections::{btree_map::Entry, BTreeMap};
+use std::path::{Path, PathBuf};
+
+use crate::web_api::{
+    behaviour::{FetchHighlightReels, FetchMediaComments, FetchUserFeed, FetchUserInfos},
+    domain::{Media, MediaComment, MediaId, Timestamp, UserId},
+    download::{Download, Downloadable, Downloader},
+    error::ClientError,
+    options::{FetchMediaCommentsOptions, FetchUserFeedOptions},
+    response::UserInfosError,
+};
+
+/// Name of the manifest at the root of an archive
+pub const MANIFEST_FILE: &str = "manifest.json";
+
+/// Medias requested per page of the feed
+const FEED_PAGE_SIZE: i16 = 50;
+
+/// Comments requested per page of a media's comments
+const COMMENTS_PAGE_SIZE: i16 = 50;
+
+#[derive(Debug, PartialEq)]
+pub enum ArchiveError {
+    UserNotFound,
+    Client(ClientError),
+    /// A file of the archive couldn't be read or written
+    UnableToWriteArchive,
+    /// The manifest left by a previous run couldn't be decoded
+    InvalidManifest,
+}
+
+impl From<ClientError> for ArchiveError {
+    fn from(error: ClientError) -> Self {
+        Self::Client(error)
+    }
+}
+
+impl From<UserInfosError> for ArchiveError {
+    fn from(error: UserInfosError) -> Self {
+        match error {
+            UserInfosError::NotFound => Self::UserNotFound,
+            UserInfosError::RateLimited(error) => Self::Client(ClientError::RateLimited(error)),
+            UserInfosError::Decode(error) => Self::Client(ClientError::Decode(error)),
+            UserInfosError::Other => Self::Client(ClientError::HttpRequest),
+        }
+    }
+}
+
+impl From<std::io::Error> for ArchiveError {
+    fn from(_error: std::io::Error) -> Self {
+        Self::UnableToWriteArchive
+    }
+}
+
+/// Index of an archive, rewritten after each page of the feed
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct Manifest {
+    pub username: String,
+    pub user_id: Option<UserId>,
+    /// Start of the last run
+    pub archived_at: Timestamp,
+    /// Whether a run went through the whole feed, the next ones stop once they reach archived
+    /// medias
+    pub feed_complete: bool,
+    /// Archived medias, by shortcode
+    pub medias: BTreeMap<String, ArchivedMedia>,
+    /// Archived highlights, by id
+    pub highlights: BTreeMap<String, ArchivedHighlight>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ArchivedMedia {
+    pub id: MediaId,
+    pub taken_at: Timestamp,
+    pub archived_at: Timestamp,
+    /// The media as returned by Instagram, with its first comments
+    pub metadata: PathBuf,
+    pub files: Vec<ArchivedFile>,
+    /// Every comment of the media, fetched by the run archiving it or by a run refreshing the
+    /// comments
+    #[serde(default)]
+    pub comments: Option<ArchivedComments>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ArchivedComments {
+    pub path: PathBuf,
+    pub count: usize,
+    pub archived_at: Timestamp,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ArchivedHighlight {
+    pub title: String,
+    pub metadata: PathBuf,
+    /// Files of the archived stories, by story id
+    pub items: BTreeMap<String, Vec<ArchivedFile>>,
+}
+
+/// Downloaded file, its path is relative to the archive
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ArchivedFile {
+    pub path: PathBuf,
+    pub url: String,
+    pub content_type: Option<String>,
+    pub length: u64,
+    pub sha256: String,
+}
+
+impl ArchivedFile {
+    fn new(download: Download, root: &Path) -> Self {
+        Self {
+            path: download
+                .path
+                .as_deref()
+                .and_then(|path| path.strip_prefix(root).ok())
+                .map(Path::to_path_buf)
+                .unwrap_or_default(),
+            url: download.url,
+            content_type: download.content_type,
+            length: download.length,
+            sha256: download.sha256,
+        }
+    }
+}
+
+/// Export an account to a directory
+///
+/// Each account is archived in its own directory:
+///
+/// ```text
+/// <dir>/<username>/
+/// ├── manifest.json
+/// ├── profile.json
+/// ├── medias/<shortcode>.json
+/// ├── medias/<shortcode>.comments.json
+/// ├── medias/<shortcode>/<media id>.<extension>
+/// ├── highlights/<highlight id>.json
+/// └── highlights/<highlight id>/<story id>.<extension>
+/// ```
+///
+/// Running it again on the same directory only fetches the medias and stories missing from the
+/// manifest with the comments of these medias, the profile is always refreshed. The comments of
+/// the medias archived before are only fetched again with
+/// [`set_refresh_comments`](Archiver::set_refresh_comments).
+///
+/// # Examples
+///
+/// ```rust
+/// use instagram::web_api::archive::Archiver;
+/// use instagram::web_api::Client;
+/// # async fn doc() {
+/// let client = Client::new();
+///
+/// let manifest = Archiver::new(&client, "archives".as_ref())
+///     .set_downloader(client.downloader())
+///     .archive("freyskeyd")
+///     .await
+///     .expect("Unable to archive account");
+///
+/// println!("{} medias archived", manifest.medias.len());
+/// # }
+/// ```
+#[derive(Debug)]
+pub struct Archiver<'c, C> {
+    client: &'c C,
+    downloader: Downloader,
+    dir: PathBuf,
+    refresh_comments: bool,
+}
+
+impl<'c, C: Sync> Archiver<'c, C> {
+    #[must_use]
+    pub fn new(client: &'c C, dir: &Path) -> Self {
+        Self {
+            client,
+            downloader: Downloader::new(),
+            dir: dir.to_path_buf(),
+            refresh_comments: false,
+        }
+    }
+
+    /// Downloader fetching the files, to choose their size or to send them through the
+    /// transport of the client with [`Client::downloader`](crate::web_api::Client::downloader)
+    #[must_use]
+    pub fn set_downloader(mut self, downloader: Downloader) -> Self {
+        self.downloader = downloader;
+
+        self
+    }
+
+    /// Fetch again every comment of the medias archived by the previous runs, each of their
+    /// pages is requested
+    #[must_use]
+    pub const fn set_refresh_comments(mut self, refresh_comments: bool) -> Self {
+        self.refresh_comments = refresh_comments;
+
+        self
+    }
+
+    async fn read_manifest(root: &Path) -> Result<Manifest, ArchiveError> {
+        match tokio::fs::read(root.join(MANIFEST_FILE)).await {
+            Ok(manifest) => {
+                serde_json::from_slice(&manifest).map_err(|_| ArchiveError::InvalidManifest)
+            }
+            Err(error) if error.kind() == std::io::ErrorKind::NotFound => Ok(Manifest::default()),
+            Err(error) => Err(error.into()),
+        }
+    }
+
+    async fn write_json<T: Serialize + Sync>(path: &Path, value: &T) -> Result<(), ArchiveError> {
+        let json =
+            serde_json::to_vec_pretty(value).map_err(|_| ArchiveError::UnableToWriteArchive)?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        Ok(tokio::fs::write(path, json).await?)
+    }
+
+    async fn download<D>(
+        &self,
+        media: &D,
+        root: &Path,
+        dir: &Path,
+    ) -> Result<Vec<ArchivedFile>, ArchiveError>
+    where
+        D: Downloadable + Sync + ?Sized,
+    {
+        let downloads = self
+            .downloader
+            .download_to_dir(media, &root.join(dir))
+            .await?;
+
+        Ok(downloads
+            .into_iter()
+            .map(|download| ArchivedFile::new(download, root))
+            .collect())
+    }
+
+    async fn archive_media(
+        &self,
+        media: &Media,
+        root: &Path,
+    ) -> Result<ArchivedMedia, ArchiveError> {
+        let dir = Path::new("medias").join(media.shortcode().to_string());
+        let metadata = dir.with_extension("json");
+
+        Self::write_json(&root.join(&metadata), media).await?;
+
+        Ok(ArchivedMedia {
+            id: media.id(),
+            taken_at: media.taken_at_timestamp(),
+            archived_at: Timestamp::now(),
+            files: self.download(media, root, &dir).await?,
+            metadata,
+            comments: None,
+        })
+    }
+}
+
+impl<C> Archiver<'_, C>
+where
+    C: FetchUserInfos + FetchUserFeed + FetchMediaComments + Sync,
+{
+    /// Archive the profile and the feed of a user, with the comments of each media
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the user doesn't exist, a request fails or the archive can't be
+    /// written. The medias archived before the failure are kept in the manifest.
+    pub async fn archive(&self, username: &str) -> Result<Manifest, ArchiveError> {
+        let (root, mut manifest) = self.archive_profile(username).await?;
+
+        self.archive_feed(&root, &mut manifest).await?;
+        self.archive_comments(&root, &mut manifest).await?;
+
+        Ok(manifest)
+    }
+
+    async fn archive_profile(&self, username: &str) -> Result<(PathBuf, Manifest), ArchiveError> {
+        let user = self.client.fetch_user_infos(username).await?;
+        let root = self.dir.join(&user.username);
+
+        let mut manifest = Self::read_manifest(&root).await?;
+
+        manifest.username = user.username.clone();
+        manifest.user_id = Some(user.id);
+        manifest.archived_at = Timestamp::now();
+
+        Self::write_json(&root.join("profile.json"), &user).await?;
+        Self::write_json(&root.join(MANIFEST_FILE), &manifest).await?;
+
+        Ok((root, manifest))
+    }
+
+    async fn archive_feed(&self, root: &Path, manifest: &mut Manifest) -> Result<(), ArchiveError> {
+        let user_id = manifest.user_id.ok_or(ArchiveError::UserNotFound)?;
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut options = FetchUserFeedOptions::default().set_count(FEED_PAGE_SIZE);
+
+            if let Some(ref after) = cursor {
+                options = options.set_after(after);
+            }
+
+            let page = self.client.fetch_user_feed(user_id, Some(options)).await?;
+
+            // Pinned medias come first, the end of the page tells whether the older ones are known
+            let reached_archived = page
+                .medias
+                .last()
+                .is_some_and(|media| manifest.medias.contains_key(&media.shortcode().to_string()));
+
+            for media in &page.medias {
+                if let Entry::Vacant(entry) = manifest.medias.entry(media.shortcode().to_string()) {
+                    entry.insert(self.archive_media(media, root).await?);
+                }
+            }
+
+            let next_cursor = page
+                .pagination_infos
+                .end_cursor()
+                .filter(|_| page.pagination_infos.has_next_page());
+
+            if next_cursor.is_none() {
+                manifest.feed_complete = true;
+            }
+
+            Self::write_json(&root.join(MANIFEST_FILE), manifest).await?;
+
+            match next_cursor {
+                Some(_) if reached_archived && manifest.feed_complete => break,
+                Some(next_cursor) => cursor = Some(next_cursor.to_string()),
+                None => break,
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn fetch_comments(&self, media_id: MediaId) -> Result<Vec<MediaComment>, ArchiveError> {
+        let mut comments = Vec::new();
+        let mut cursor: Option<String> = None;
+
+        loop {
+            let mut options = FetchMediaCommentsOptions::default().set_count(COMMENTS_PAGE_SIZE);
+
+            if let Some(ref after) = cursor {
+                options = options.set_after(after);
+            }
+
+            let page = self
+                .client
+                .fetch_media_comments(media_id, Some(options))
+                .await?;
+
+            let next_cursor = page
+                .pagination_infos()
+                .end_cursor()
+                .filter(|_| page.pagination_infos().has_next_page())
+                .map(str::to_string);
+
+            comments.extend(page.into_comments());
+
+            match next_cursor {
+                Some(next_cursor) => cursor = Some(next_cursor),
+                None => break,
+            }
+        }
+
+        Ok(comments)
+    }
+
+    /// Write every comment of the medias which have none yet, or of all the archived medias when
+    /// the comments are refreshed
+    async fn archive_comments(
+        &self,
+        root: &Path,
+        manifest: &mut Manifest,
+    ) -> Result<(), ArchiveError> {
+        let medias = manifest
+            .medias
+            .iter_mut()
+            .filter(|(_, archived)| self.refresh_comments || archived.comments.is_none());
+
+        for (shortcode, archived) in medias {
+            let comments = self.fetch_comments(archived.id).await?;
+            let path = Path::new("medias").join(format!("{shortcode}.comments.json"));
+
+            Self::write_json(&root.join(&path), &comments).await?;
+
+            archived.comments = Some(ArchivedComments {
+                path,
+                count: comments.len(),
+                archived_at: Timestamp::now(),
+            });
+        }
+
+        Self::write_json(&root.join(MANIFEST_FILE), manifest).await
+    }
+}
+
+impl<C> Archiver<'_, C>
+where
+    C: FetchUserInfos + FetchUserFeed + FetchMediaComments + FetchHighlightReels + Sync,
+{
+    /// Archive the profile, the feed with its comments and the highlights of a user
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the user doesn't exist, a request fails or the archive can't be
+    /// written. The medias archived before the failure are kept in the manifest.
+    pub async fn archive_with_highlights(&self, username: &str) -> Result<Manifest, ArchiveError> {
+        let (root, mut manifest) = self.archive_profile(username).await?;
+
+        self.archive_feed(&root, &mut manifest).await?;
+        self.archive_comments(&root, &mut manifest).await?;
+        self.archive_highlights(&root, &mut manifest).await?;
+
+        Ok(manifest)
+    }
+
+    async fn archive_highlights(
+        &self,
+        root: &Path,
+        manifest: &mut Manifest,
+    ) -> Result<(), ArchiveError> {
+        let user_id = manifest.user_id.ok_or(ArchiveError::UserNotFound)?;
+
+        for highlight in self.client.fetch_highlight_reels(user_id).await? {
+            let id = highlight.id.trim_start_matches("highlight:").to_string();
+            let dir = Path::new("highlights").join(&id);
+            let metadata = dir.with_extension("json");
+
+            Self::write_json(&root.join(&metadata), &highlight).await?;
+
+            let archived = manifest
+                .highlights
+                .entry(id)
+                .or_insert_with(|| ArchivedHighlight {
+                    title: highlight.title.clone(),
+                    metadata,
+                    items: BTreeMap::new(),
+                });
+
+            archived.title.clone_from(&highlight.title);
+
+            for item in &highlight.items {
+                if !archived.items.contains_key(&item.id) {
+                    let files = self.download(item, root, &dir).await?;
+
+                    archived.items.insert(item.id.clone(), files);
+                }
+            }
+        }
+
+        Self::write_json(&root.join(MANIFEST_FILE), manifest).await
+    }
+}
@@ -1,9 +1,15 @@
 use async_trait::async_trait;
 
 use crate::web_api::{
-    domain::{UserFeed, UserInfos},
+    domain::{
+        Highlight, Media, MediaArchiving, MediaCaptionEdition, MediaComments, MediaDeletion,
+        MediaId, Reel, ReelItem, ReelsTray, StoriesSeen, TimelineFeed, UserFeed, UserId, UserInfos,
+    },
     error::ClientError,
-    options::FetchUserFeedOptions,
+    options::{
+        FetchMediaCommentsOptions, FetchTimelineFeedOptions, FetchUserFeedOptions, Photo,
+        PostPhotoOptions,
+    },
     response::UserInfosError,
 };
 
@@ -46,8 +52,8 @@ pub trait FetchUserFeed {
     /// # async fn doc() -> Result<(), reqwest::Error> {
     /// let client = Client::new();
     ///
-    /// let user_id = "some_id";
-    /// let some_user_feed: UserFeed = client.fetch_user_feed(&user_id, None).await.expect(
+    /// let user_id = "8999249".parse().expect("Invalid user id");
+    /// let some_user_feed: UserFeed = client.fetch_user_feed(user_id, None).await.expect(
     ///     "Unable to
     /// retriev user feed",
     /// );
@@ -60,8 +66,8 @@ pub trait FetchUserFeed {
     /// Will return `Err` if the request fails on instagram api.
     async fn fetch_user_feed(
         &self,
-        user_id: &str,
-        options: Option<FetchUserFeedOptions<'_, '_>>,
+        user_id: UserId,
+        options: Option<FetchUserFeedOptions<'_>>,
     ) -> Result<UserFeed, ClientError>;
 }
 
@@ -71,17 +77,17 @@ pub trait FetchMediaInfos {
     ///
     /// # Examples
     ///
-    /// ```rust,ignore
-    /// use instagram::web_api::Client;
+    /// ```rust
     /// use instagram::web_api::behaviour::FetchMediaInfos;
+    /// use instagram::web_api::Client;
     /// # async fn doc() -> Result<(), reqwest::Error> {
     /// let client = Client::new();
     ///
-    /// let media_id = "some_id";
+    /// let media_id = "2250382848586272543".parse().unwrap();
     /// let some_media = client
-    ///
-    /// .fetch_media_infos(&media_id).await.expect("Unable to
-    /// retriev media info");
+    ///     .fetch_media_infos(media_id)
+    ///     .await
+    ///     .expect("Unable to retrieve media info");
     /// # Ok(())
     /// # }
     /// ```
@@ -89,10 +95,48 @@ pub trait FetchMediaInfos {
     /// # Errors
     ///
     /// Will return `Err` if the request fails on instagram api.
-    async fn fetch_media_infos(&self, media_id: &str) -> Result<(), ClientError>;
+    async fn fetch_media_infos(&self, media_id: MediaId) -> Result<Media, ClientError>;
+}
+
+#[async_trait]
+pub trait FetchMediaComments {
+    /// Fetch a page of a media's comments
+    ///
+    /// The next page is fetched with the `end_cursor` of this one as `after` option.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::behaviour::FetchMediaComments;
+    /// use instagram::web_api::options::FetchMediaCommentsOptions;
+    /// use instagram::web_api::Client;
+    /// # async fn doc() {
+    /// let client = Client::new();
+    ///
+    /// let media_id = "2250382848586272543".parse().unwrap();
+    /// let options = FetchMediaCommentsOptions::default().set_count(50);
+    ///
+    /// let page = client
+    ///     .fetch_media_comments(media_id, Some(options))
+    ///     .await
+    ///     .expect("Unable to retrieve comments");
+    ///
+    /// for comment in page.comments() {
+    ///     println!("{}", comment.text());
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn fetch_media_comments(
+        &self,
+        media_id: MediaId,
+        options: Option<FetchMediaCommentsOptions<'_>>,
+    ) -> Result<MediaComments, ClientError>;
 }
 
-pub trait FetchMediaComments {}
 pub trait Search {}
 pub trait FetchTagFeed {}
 pub trait FetchLocationFeed {}
@@ -108,13 +152,228 @@ pub trait FetchUserFollowing {}
 pub trait FetchUserFollowers {}
 pub trait LikePost {}
 pub trait CancelPostLike {}
-pub trait DeleteMedia {}
+
+#[async_trait]
+pub trait DeleteMedia {
+    /// Delete one of the logged in user's media
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::behaviour::DeleteMedia;
+    /// # async fn doc(client: instagram::web_api::AuthenticatedClient) {
+    /// let media_id = "2250382848586272543".parse().unwrap();
+    /// let deletion = client.delete_media(media_id).await.expect("Unable to delete media");
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn delete_media(&self, media_id: MediaId) -> Result<MediaDeletion, ClientError>;
+}
+
+#[async_trait]
+pub trait ArchiveMedia {
+    /// Hide one of the logged in user's media from its profile
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn archive_media(&self, media_id: MediaId) -> Result<MediaArchiving, ClientError>;
+
+    /// Restore an archived media on the logged in user's profile
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn unarchive_media(&self, media_id: MediaId) -> Result<MediaArchiving, ClientError>;
+}
+
+#[async_trait]
+pub trait EditMediaCaption {
+    /// Replace the caption of one of the logged in user's media
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn edit_media_caption(
+        &self,
+        media_id: MediaId,
+        caption: &str,
+    ) -> Result<MediaCaptionEdition, ClientError>;
+}
+
 pub trait Follow {}
 pub trait UnFollow {}
 pub trait CommentPost {}
 pub trait DeleteComment {}
-pub trait PostPhoto {}
-pub trait FetchTimelineFeed {}
-pub trait FetchReelsTray {}
-pub trait FetchReelsFeed {}
-pub trait FetchHighlightReels {}
+
+#[async_trait]
+pub trait PostPhoto {
+    /// Publish a JPEG photo on the logged in user's profile
+    ///
+    /// The photo is uploaded first, then configured with the caption, location and user tags
+    /// of the options.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::behaviour::PostPhoto;
+    /// use instagram::web_api::options::{Photo, PostPhotoOptions};
+    /// # async fn doc(client: instagram::web_api::AuthenticatedClient) {
+    /// let options = PostPhotoOptions::default().set_caption("Hello #world");
+    ///
+    /// let media = client
+    ///     .post_photo(Photo::Path("photo.jpg".as_ref()), Some(options))
+    ///     .await
+    ///     .expect("Unable to post photo");
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the photo can't be read, isn't a JPEG or if the request fails on
+    /// instagram api.
+    async fn post_photo(
+        &self,
+        photo: Photo<'_>,
+        options: Option<PostPhotoOptions<'_>>,
+    ) -> Result<Media, ClientError>;
+}
+
+#[async_trait]
+pub trait FetchTimelineFeed {
+    /// Fetch a page of the logged in user's home timeline
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::behaviour::FetchTimelineFeed;
+    /// use instagram::web_api::domain::TimelineItem;
+    /// # async fn doc(client: instagram::web_api::AuthenticatedClient) {
+    /// let timeline = client
+    ///     .fetch_timeline_feed(None)
+    ///     .await
+    ///     .expect("Unable to retrieve timeline");
+    ///
+    /// for item in timeline.items {
+    ///     if let TimelineItem::Media(media) = item {
+    ///         println!("{:?}", media);
+    ///     }
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn fetch_timeline_feed(
+        &self,
+        options: Option<FetchTimelineFeedOptions<'_>>,
+    ) -> Result<TimelineFeed, ClientError>;
+}
+
+#[async_trait]
+pub trait FetchReelsTray {
+    /// Fetch the logged in user's stories tray
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::behaviour::FetchReelsTray;
+    /// # async fn doc(client: instagram::web_api::AuthenticatedClient) {
+    /// let tray = client.fetch_reels_tray().await.expect("Unable to retrieve tray");
+    ///
+    /// for reel in tray.unseen() {
+    ///     println!("{} has new stories", reel.user.username);
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn fetch_reels_tray(&self) -> Result<ReelsTray, ClientError>;
+}
+
+#[async_trait]
+pub trait FetchReelsFeed {
+    /// Fetch the stories of many users at once
+    ///
+    /// Users ids are sent by batches, users without any story are left out of the result.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::behaviour::FetchReelsFeed;
+    /// use instagram::web_api::domain::UserId;
+    /// # async fn doc(client: instagram::web_api::AuthenticatedClient) {
+    /// let reels = client
+    ///     .fetch_reels_feed(&[UserId::new(8_999_249), UserId::new(1_471_705_399)])
+    ///     .await
+    ///     .expect("Unable to retrieve reels");
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn fetch_reels_feed(&self, user_ids: &[UserId]) -> Result<Vec<Reel>, ClientError>;
+}
+
+#[async_trait]
+pub trait FetchHighlightReels {
+    /// Fetch the highlights of a user along with their stories
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::behaviour::FetchHighlightReels;
+    /// use instagram::web_api::domain::UserId;
+    /// # async fn doc(client: instagram::web_api::AuthenticatedClient) {
+    /// let highlights = client
+    ///     .fetch_highlight_reels(UserId::new(8_999_249))
+    ///     .await
+    ///     .expect("Unable to retrieve highlights");
+    ///
+    /// for highlight in highlights {
+    ///     println!("{}: {} stories", highlight.title, highlight.items.len());
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn fetch_highlight_reels(&self, user_id: UserId) -> Result<Vec<Highlight>, ClientError>;
+}
+
+#[async_trait]
+pub trait MarkStoriesAsSeen {
+    /// Mark stories returned by the reels behaviours as seen by the logged in user
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::behaviour::{FetchReelsFeed, MarkStoriesAsSeen};
+    /// use instagram::web_api::domain::UserId;
+    /// # async fn doc(client: instagram::web_api::AuthenticatedClient) {
+    /// let reels = client.fetch_reels_feed(&[UserId::new(8_999_249)]).await.unwrap();
+    ///
+    /// for reel in reels {
+    ///     let items: Vec<_> = reel.items.iter().collect();
+    ///
+    ///     client
+    ///         .mark_stories_as_seen(&items)
+    ///         .await
+    ///         .expect("Unable to mark stories as seen");
+    /// }
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    async fn mark_stories_as_seen(&self, items: &[&ReelItem]) -> Result<StoriesSeen, ClientError>;
+}
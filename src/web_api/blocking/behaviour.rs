@@ -0,0 +1,58 @@
+use crate::web_api::{
+    domain::{UserFeed, UserId, UserInfos},
+    error::ClientError,
+    options::FetchUserFeedOptions,
+    response::UserInfosError,
+};
+
+pub trait FetchUserInfos {
+    /// Fetch user's informations, blocking the current thread
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::blocking::behaviour::FetchUserInfos;
+    /// use instagram::web_api::blocking::Client;
+    /// use instagram::web_api::domain::UserInfos;
+    /// # fn doc() {
+    ///
+    /// let some_user_info: UserInfos = Client::new()
+    ///     .fetch_user_infos("SomeUser")
+    ///     .expect("Unable to retrieve user infos");
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    fn fetch_user_infos(&self, username: &str) -> Result<UserInfos, UserInfosError>;
+}
+
+pub trait FetchUserFeed {
+    /// Fetch user's feed, blocking the current thread
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::blocking::behaviour::FetchUserFeed;
+    /// use instagram::web_api::blocking::Client;
+    /// use instagram::web_api::domain::UserFeed;
+    /// # fn doc() {
+    /// let client = Client::new();
+    ///
+    /// let user_id = "8999249".parse().expect("Invalid user id");
+    /// let some_user_feed: UserFeed = client
+    ///     .fetch_user_feed(user_id, None)
+    ///     .expect("Unable to retrieve user feed");
+    /// # }
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    fn fetch_user_feed(
+        &self,
+        user_id: UserId,
+        options: Option<FetchUserFeedOptions<'_>>,
+    ) -> Result<UserFeed, ClientError>;
+}
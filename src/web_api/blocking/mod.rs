@@ -0,0 +1,195 @@
+//! Synchronous clients, for programs which don't run an async runtime
+//!
+//! Each client owns a single threaded runtime, its calls block the current thread until the
+//! response is decoded. They mustn't be used from within an async runtime.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use instagram::web_api::blocking::behaviour::FetchUserInfos;
+//! use instagram::web_api::blocking::Client;
+//! # fn doc() {
+//!
+//! let user = Client::new()
+//!     .fetch_user_infos("freyskeyd")
+//!     .expect("Unable to retrieve user infos");
+//! # }
+//! ```
+use std::future::Future;
+use std::sync::{Mutex, PoisonError};
+
+use crate::web_api::{
+    self,
+    credentials::Credentials,
+    domain::{UserFeed, UserId, UserInfos},
+    error::ClientError,
+    options::FetchUserFeedOptions,
+    response::UserInfosError,
+    Session,
+};
+
+pub mod behaviour;
+
+use behaviour::{FetchUserFeed, FetchUserInfos};
+
+/// Runtime driving the requests of a blocking client
+#[derive(Debug)]
+struct Runtime(Mutex<tokio::runtime::Runtime>);
+
+impl Runtime {
+    fn new() -> Self {
+        let runtime = tokio::runtime::Builder::new()
+            .basic_scheduler()
+            .enable_all()
+            .build()
+            .expect("Unable to start the runtime of the blocking client");
+
+        Self(Mutex::new(runtime))
+    }
+
+    fn block_on<F: Future>(&self, future: F) -> F::Output {
+        self.0
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .block_on(future)
+    }
+}
+
+/// Blocking version of [`web_api::Client`]
+#[derive(Debug)]
+pub struct Client {
+    inner: web_api::Client,
+    runtime: Runtime,
+}
+
+impl std::default::Default for Client {
+    fn default() -> Self {
+        Self::from(web_api::Client::default())
+    }
+}
+
+/// Wrap an async client, keeping its configuration
+///
+/// # Examples
+///
+/// ```rust
+/// use instagram::web_api::{blocking, Client};
+///
+/// let client = blocking::Client::from(Client::new().lenient());
+/// ```
+impl From<web_api::Client> for Client {
+    fn from(inner: web_api::Client) -> Self {
+        Self {
+            inner,
+            runtime: Runtime::new(),
+        }
+    }
+}
+
+impl Client {
+    /// Create a new client with default configuration.
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the runtime of the client can't be started.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::blocking::Client;
+    ///
+    /// let client = Client::new();
+    /// ```
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Login with provided credentials, see [`web_api::Client::login`]
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails on instagram api.
+    /// Maybe due to an unknown error or a mistake in the credentials
+    pub fn login(self, credentials: &Credentials<'_>) -> Result<AuthenticatedClient, ClientError> {
+        let Self { inner, runtime } = self;
+        let inner = runtime.block_on(inner.login(credentials))?;
+
+        Ok(AuthenticatedClient { inner, runtime })
+    }
+}
+
+impl FetchUserInfos for Client {
+    fn fetch_user_infos(&self, username: &str) -> Result<UserInfos, UserInfosError> {
+        use web_api::behaviour::FetchUserInfos;
+
+        self.runtime.block_on(self.inner.fetch_user_infos(username))
+    }
+}
+
+impl FetchUserFeed for Client {
+    fn fetch_user_feed(
+        &self,
+        user_id: UserId,
+        options: Option<FetchUserFeedOptions<'_>>,
+    ) -> Result<UserFeed, ClientError> {
+        use web_api::behaviour::FetchUserFeed;
+
+        self.runtime
+            .block_on(self.inner.fetch_user_feed(user_id, options))
+    }
+}
+
+/// Blocking version of [`web_api::AuthenticatedClient`]
+#[derive(Debug)]
+pub struct AuthenticatedClient {
+    inner: web_api::AuthenticatedClient,
+    runtime: Runtime,
+}
+
+impl From<web_api::AuthenticatedClient> for AuthenticatedClient {
+    fn from(inner: web_api::AuthenticatedClient) -> Self {
+        Self {
+            inner,
+            runtime: Runtime::new(),
+        }
+    }
+}
+
+impl AuthenticatedClient {
+    /// Resume a session saved with `session`, without logging in again
+    #[must_use]
+    pub fn from_session(client: Client, session: Session) -> Self {
+        Self {
+            inner: web_api::AuthenticatedClient::from_session(client.inner, session),
+            runtime: client.runtime,
+        }
+    }
+
+    /// Tokens and cookies of the client, to resume it later with `from_session`
+    #[must_use]
+    pub fn session(&self) -> Session {
+        self.inner.session()
+    }
+}
+
+impl FetchUserInfos for AuthenticatedClient {
+    fn fetch_user_infos(&self, username: &str) -> Result<UserInfos, UserInfosError> {
+        use web_api::behaviour::FetchUserInfos;
+
+        self.runtime.block_on(self.inner.fetch_user_infos(username))
+    }
+}
+
+impl FetchUserFeed for AuthenticatedClient {
+    fn fetch_user_feed(
+        &self,
+        user_id: UserId,
+        options: Option<FetchUserFeedOptions<'_>>,
+    ) -> Result<UserFeed, ClientError> {
+        use web_api::behaviour::FetchUserFeed;
+
+        self.runtime
+            .block_on(self.inner.fetch_user_feed(user_id, options))
+    }
+}
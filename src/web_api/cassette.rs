@@ -0,0 +1,431 @@
+//! Record the exchanges of a client with Instagram and replay them offline
+//!
+//! A [`RecordTransport`] saves every request and its response to a cassette file, with the
+//! cookies, tokens and credentials redacted, in the headers as well as in the bodies. A
+//! [`ReplayTransport`] answers the requests from that file, a request which wasn't recorded
+//! fails.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use instagram::web_api::cassette::{RecordTransport, ReplayTransport};
+//! use instagram::web_api::transport::ReqwestTransport;
+//! use instagram::web_api::Client;
+//! use std::path::Path;
+//! # fn doc() -> std::io::Result<()> {
+//! let path = Path::new("tests/cassettes/login.json");
+//!
+//! let client = if std::env::var("INSTAGRAM_RECORD").is_ok() {
+//!     Client::new().with_transport(RecordTransport::new(ReqwestTransport::new(), path))
+//! } else {
+//!     Client::new().with_transport(ReplayTransport::load(path)?)
+//! };
+//! # Ok(())
+//! # }
+//! ```
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::io;
+use std::path::{Path, PathBuf};
+use std::sync::{Mutex, MutexGuard, PoisonError};
+
+use crate::web_api::transport::{
+    HeaderMap, HeaderName, HeaderValue, Request, Response, StatusCode, Transport, TransportError,
+};
+
+/// Value replacing the secrets in a cassette
+pub const REDACTED: &str = "REDACTED";
+
+/// Request headers holding the session or a token
+const SECRET_HEADERS: [&str; 4] = ["authorization", "cookie", "x-csrftoken", "x-instagram-ajax"];
+
+/// Form fields holding the credentials
+const SECRET_FIELDS: [&str; 3] = ["username", "password", "enc_password"];
+
+/// JSON fields of the response bodies holding a token, in the pages' shared data too
+const SECRET_BODY_FIELDS: [&str; 1] = ["csrf_token"];
+
+/// Cookie values shorter than this, like flags, aren't looked for in the response bodies
+const MIN_COOKIE_LENGTH: usize = 6;
+
+/// Requests and responses recorded in a file
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Cassette {
+    pub interactions: Vec<Interaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Interaction {
+    pub request: RecordedRequest,
+    pub response: RecordedResponse,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RecordedRequest {
+    pub method: String,
+    pub url: String,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: RecordedBody,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+pub struct RecordedResponse {
+    pub status: u16,
+    #[serde(default)]
+    pub headers: Vec<(String, String)>,
+    #[serde(default)]
+    pub body: RecordedBody,
+}
+
+/// Body of a request or a response, kept as text unless it isn't UTF-8
+#[derive(Debug, Clone, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(untagged)]
+pub enum RecordedBody {
+    Text(String),
+    Binary { hex: String },
+}
+
+impl Default for RecordedBody {
+    fn default() -> Self {
+        Self::Text(String::new())
+    }
+}
+
+impl RecordedBody {
+    fn new(body: Vec<u8>) -> Self {
+        match String::from_utf8(body) {
+            Ok(text) => Self::Text(text),
+            Err(error) => Self::Binary {
+                hex: hex::encode(error.as_bytes()),
+            },
+        }
+    }
+
+    fn to_bytes(&self) -> Vec<u8> {
+        match self {
+            Self::Text(text) => text.as_bytes().to_vec(),
+            Self::Binary { hex } => hex::decode(hex).unwrap_or_default(),
+        }
+    }
+}
+
+fn is_form(headers: &HeaderMap) -> bool {
+    headers
+        .get("content-type")
+        .and_then(|value| value.to_str().ok())
+        .is_some_and(|value| value.starts_with("application/x-www-form-urlencoded"))
+}
+
+/// Replace the credentials of a form
+fn redact_form(body: &[u8]) -> Vec<u8> {
+    let Ok(fields) = serde_urlencoded::from_bytes::<Vec<(String, String)>>(body) else {
+        return body.to_vec();
+    };
+
+    let fields: Vec<(String, String)> = fields
+        .into_iter()
+        .map(|(name, value)| {
+            if SECRET_FIELDS.contains(&name.as_str()) {
+                (name, REDACTED.to_string())
+            } else {
+                (name, value)
+            }
+        })
+        .collect();
+
+    serde_urlencoded::to_string(fields)
+        .map(String::into_bytes)
+        .unwrap_or_default()
+}
+
+/// Replace the value of every `"<field>":"<value>"` of a text
+fn redact_field(text: &str, field: &str) -> String {
+    let key = format!("\"{field}\"");
+    let mut redacted = String::with_capacity(text.len());
+    let mut rest = text;
+
+    while let Some(start) = rest.find(&key) {
+        let (before, after) = rest.split_at(start + key.len());
+
+        redacted.push_str(before);
+
+        let value = after
+            .trim_start()
+            .strip_prefix(':')
+            .map(str::trim_start)
+            .and_then(|value| value.strip_prefix('"'))
+            .and_then(|value| value.find('"').map(|end| (value, end)));
+
+        rest = match value {
+            Some((value, end)) => {
+                redacted.push_str(&after[..after.len() - value.len()]);
+                redacted.push_str(REDACTED);
+
+                &value[end..]
+            }
+            None => after,
+        };
+    }
+
+    redacted.push_str(rest);
+
+    redacted
+}
+
+/// Replace the tokens of a response body and the values of the cookies set along with it
+fn redact_body(body: &[u8], cookies: &[(String, String)]) -> Vec<u8> {
+    let Ok(text) = std::str::from_utf8(body) else {
+        return body.to_vec();
+    };
+
+    let mut text = SECRET_BODY_FIELDS
+        .iter()
+        .fold(text.to_string(), |text, field| redact_field(&text, field));
+
+    for (_, value) in cookies {
+        if value.len() >= MIN_COOKIE_LENGTH {
+            text = text.replace(value.as_str(), REDACTED);
+        }
+    }
+
+    text.into_bytes()
+}
+
+fn headers_to_vec(headers: &HeaderMap) -> Vec<(String, String)> {
+    headers
+        .iter()
+        .map(|(name, value)| {
+            (
+                name.to_string(),
+                String::from_utf8_lossy(value.as_bytes()).into_owned(),
+            )
+        })
+        .collect()
+}
+
+impl RecordedRequest {
+    /// Record a request, without its secrets
+    #[must_use]
+    pub fn new(request: &Request) -> Self {
+        let headers = headers_to_vec(&request.headers)
+            .into_iter()
+            .map(|(name, value)| {
+                if SECRET_HEADERS.contains(&name.as_str()) {
+                    (name, REDACTED.to_string())
+                } else {
+                    (name, value)
+                }
+            })
+            .collect();
+
+        let body = if is_form(&request.headers) {
+            redact_form(&request.body)
+        } else {
+            request.body.clone()
+        };
+
+        Self {
+            method: request.method.to_string(),
+            url: request.url.clone(),
+            headers,
+            body: RecordedBody::new(body),
+        }
+    }
+
+    /// Whether the recorded request is the same as this one, its headers aside
+    #[must_use]
+    pub fn matches(&self, request: &Self) -> bool {
+        self.method == request.method && self.url == request.url && self.body == request.body
+    }
+}
+
+impl RecordedResponse {
+    /// Record a response, the values of the cookies it sets and the tokens of its body are
+    /// redacted
+    #[must_use]
+    pub fn new(response: &Response) -> Self {
+        let headers = headers_to_vec(&response.headers)
+            .into_iter()
+            .map(|(name, value)| {
+                if name == "set-cookie" {
+                    let cookie = value.split('=').next().unwrap_or_default();
+
+                    (name, format!("{cookie}={REDACTED}"))
+                } else {
+                    (name, value)
+                }
+            })
+            .collect();
+
+        Self {
+            status: response.status.as_u16(),
+            headers,
+            body: RecordedBody::new(redact_body(&response.body, &response.cookies())),
+        }
+    }
+
+    fn to_response(&self) -> Response {
+        let mut headers = HeaderMap::new();
+
+        for (name, value) in &self.headers {
+            if let (Ok(name), Ok(value)) = (
+                HeaderName::from_bytes(name.as_bytes()),
+                HeaderValue::from_str(value),
+            ) {
+                headers.append(name, value);
+            }
+        }
+
+        Response {
+            status: StatusCode::from_u16(self.status).unwrap_or(StatusCode::OK),
+            headers,
+            body: self.body.to_bytes(),
+        }
+    }
+}
+
+impl Cassette {
+    /// # Errors
+    ///
+    /// Will return `Err` if the file can't be read or isn't a cassette.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        serde_json::from_slice(&std::fs::read(path)?)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the file or its directory can't be written.
+    pub async fn save(&self, path: &Path) -> io::Result<()> {
+        let json = serde_json::to_vec_pretty(self)
+            .map_err(|error| io::Error::new(io::ErrorKind::InvalidData, error))?;
+
+        if let Some(parent) = path.parent() {
+            tokio::fs::create_dir_all(parent).await?;
+        }
+
+        tokio::fs::write(path, json).await
+    }
+}
+
+/// Transport sending the requests through another one and saving them to a cassette
+///
+/// The cassette is written after each response, it's replaced when the recording starts.
+#[derive(Debug)]
+pub struct RecordTransport<T> {
+    inner: T,
+    path: PathBuf,
+    cassette: Mutex<Cassette>,
+    /// Held while the cassette is written, so an older copy can't replace a newer one
+    saving: tokio::sync::Mutex<()>,
+}
+
+impl<T> RecordTransport<T> {
+    #[must_use]
+    pub fn new(inner: T, path: &Path) -> Self {
+        Self {
+            inner,
+            path: path.to_path_buf(),
+            cassette: Mutex::new(Cassette::default()),
+            saving: tokio::sync::Mutex::new(()),
+        }
+    }
+
+    /// Interactions recorded so far
+    #[must_use]
+    pub fn cassette(&self) -> Cassette {
+        self.lock().clone()
+    }
+
+    fn lock(&self) -> MutexGuard<'_, Cassette> {
+        self.cassette.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+}
+
+#[async_trait]
+impl<T: Transport> Transport for RecordTransport<T> {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        let recorded = RecordedRequest::new(&request);
+        let response = self.inner.send(request).await?;
+
+        self.lock().interactions.push(Interaction {
+            request: recorded,
+            response: RecordedResponse::new(&response),
+        });
+
+        let _saving = self.saving.lock().await;
+
+        self.cassette()
+            .save(&self.path)
+            .await
+            .map_err(|error| TransportError::Connection(error.to_string()))?;
+
+        Ok(response)
+    }
+}
+
+/// Transport answering the requests from a cassette, without any network
+///
+/// Each interaction answers a single request, in the order of the recording when a request was
+/// sent several times.
+#[derive(Debug)]
+pub struct ReplayTransport {
+    interactions: Mutex<Vec<(Interaction, bool)>>,
+}
+
+impl ReplayTransport {
+    #[must_use]
+    pub fn new(cassette: Cassette) -> Self {
+        Self {
+            interactions: Mutex::new(
+                cassette
+                    .interactions
+                    .into_iter()
+                    .map(|interaction| (interaction, false))
+                    .collect(),
+            ),
+        }
+    }
+
+    /// # Errors
+    ///
+    /// Will return `Err` if the file can't be read or isn't a cassette.
+    pub fn load(path: &Path) -> io::Result<Self> {
+        Cassette::load(path).map(Self::new)
+    }
+
+    /// Whether every recorded interaction was replayed
+    #[must_use]
+    pub fn is_exhausted(&self) -> bool {
+        self.interactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .iter()
+            .all(|(_, replayed)| *replayed)
+    }
+}
+
+#[async_trait]
+impl Transport for ReplayTransport {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        let recorded = RecordedRequest::new(&request);
+        let mut interactions = self
+            .interactions
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner);
+
+        interactions
+            .iter_mut()
+            .find(|(interaction, replayed)| !replayed && interaction.request.matches(&recorded))
+            .map(|(interaction, replayed)| {
+                *replayed = true;
+
+                interaction.response.to_response()
+            })
+            .ok_or_else(|| {
+                TransportError::NotRecorded(format!("{} {}", request.method, request.url))
+            })
+    }
+}
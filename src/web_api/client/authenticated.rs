@@ -1,17 +1,51 @@
+use async_trait::async_trait;
+
+use serde::de::{DeserializeOwned, IgnoredAny};
+use serde_json::json;
+
+use super::{upload, Session};
 use crate::web_api::{
-    behaviour::FetchUserInfos, domain::LoginInfos, domain::UserInfos, error::ClientError,
-    response::UserInfosError, Client,
+    behaviour::{
+        ArchiveMedia, DeleteMedia, EditMediaCaption, FetchHighlightReels, FetchMediaComments,
+        FetchMediaInfos, FetchReelsFeed, FetchReelsTray, FetchTimelineFeed, FetchUserFeed,
+        FetchUserInfos, MarkStoriesAsSeen, PostPhoto,
+    },
+    domain::{
+        Highlight, LoginInfos, Media, MediaArchiving, MediaCaptionEdition, MediaComments,
+        MediaDeletion, MediaId, Reel, ReelItem, ReelsTray, StoriesSeen, TimelineFeed, Timestamp,
+        UserFeed, UserId, UserInfos,
+    },
+    download::Downloader,
+    error::ClientError,
+    options::{
+        FetchMediaCommentsOptions, FetchTimelineFeedOptions, FetchUserFeedOptions, Photo,
+        PostPhotoOptions,
+    },
+    response::{
+        throttle, ActionResponse, ConfigureMediaResponse, EditMediaResponse, EmptyResponse,
+        GraphQLResponse, HighlightsMediaResponse, HighlightsTrayResponse, ReelsMediaResponse,
+        TimelineFeedResponse, UploadPhotoResponse, UserInfosError,
+    },
+    transport::Request,
+    Client,
 };
 
+/// Application id sent by the web front when it calls the `/api/v1` endpoints
+const WEB_APP_ID: &str = "936619743392459";
+
+/// Maximum number of reels requested at once on `feed/reels_media`
+const REELS_MEDIA_BATCH_SIZE: usize = 50;
+
 /// An authenticated Web client to access the api
 ///
 /// This client will use the private API to fetch data and proceed actions.
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct AuthenticatedClient {
     base_client: Client,
     csrf_token: Option<String>,
     init_csrf_token: Option<String>,
     rollout_hash: Option<String>,
+    session_cookies: Vec<(String, String)>,
 }
 
 impl AuthenticatedClient {
@@ -20,17 +54,435 @@ impl AuthenticatedClient {
     /// Will return `Err` if login informations isnt validated.
     pub fn from_login_infos(client: Client, _login_info: &LoginInfos) -> Result<Self, ClientError> {
         Ok(Self {
+            csrf_token: client.csrf_token.clone(),
+            init_csrf_token: client.init_csrf_token.clone(),
+            rollout_hash: client.rollout_hash.clone(),
+            base_client: client,
+            session_cookies: Vec::new(),
+        })
+    }
+
+    /// Resume a session saved with `session`, without logging in again
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::{AuthenticatedClient, Client, Session};
+    /// # fn doc(saved: &str) {
+    /// let session: Session = serde_json::from_str(saved).expect("Invalid session");
+    /// let client = AuthenticatedClient::from_session(Client::new(), session);
+    /// # }
+    /// ```
+    #[must_use]
+    pub fn from_session(client: Client, session: Session) -> Self {
+        Self {
             base_client: client,
-            csrf_token: None,
-            init_csrf_token: None,
-            rollout_hash: None,
+            csrf_token: session.csrf_token,
+            init_csrf_token: session.init_csrf_token,
+            rollout_hash: session.rollout_hash,
+            session_cookies: session.cookies,
+        }
+    }
+
+    /// Tokens and cookies of the client, to resume it later with `from_session`
+    #[must_use]
+    pub fn session(&self) -> Session {
+        Session {
+            csrf_token: self.csrf_token.clone(),
+            init_csrf_token: self.init_csrf_token.clone(),
+            rollout_hash: self.rollout_hash.clone(),
+            cookies: self.session_cookies.clone(),
+        }
+    }
+
+    /// Downloader fetching the files of the medias through the transport of the base client
+    #[must_use]
+    pub fn downloader(&self) -> Downloader {
+        self.base_client.downloader()
+    }
+
+    /// Keep the cookies set by Instagram on login, the `csrftoken` one replaces the initial token.
+    pub(crate) fn with_session_cookies(mut self, session_cookies: Vec<(String, String)>) -> Self {
+        if let Some((_, csrf_token)) = session_cookies.iter().find(|(name, _)| name == "csrftoken")
+        {
+            self.csrf_token = Some(csrf_token.clone());
+        }
+
+        self.session_cookies = session_cookies;
+
+        self
+    }
+
+    /// Add the headers of the web front and the session cookies to a request
+    fn authenticated(&self, mut request: Request) -> Request {
+        request = request
+            .header("accept", "*/*")
+            .header("accept-language", "en-US")
+            .header("x-requested-with", "XMLHttpRequest")
+            .header("x-ig-app-id", WEB_APP_ID)
+            .header("referer", "https://www.instagram.com/")
+            .header("origin", "https://www.instagram.com");
+
+        if let Some(ref csrf_token) = self.csrf_token {
+            request = request.header("x-csrftoken", csrf_token);
+        }
+
+        if let Some(ref rollout_hash) = self.rollout_hash {
+            request = request.header("x-instagram-ajax", rollout_hash);
+        }
+
+        if self.session_cookies.is_empty() {
+            return request;
+        }
+
+        let cookies = self
+            .session_cookies
+            .iter()
+            .map(|(name, value)| format!("{name}={value}"))
+            .collect::<Vec<String>>()
+            .join("; ");
+
+        request.header("cookie", &cookies)
+    }
+
+    async fn post_action<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        form: &[(&str, &str)],
+    ) -> Result<T, ClientError> {
+        let url = format!("{}{}", self.base_client.api_url, path);
+
+        let body = self
+            .base_client
+            .fetch(self.authenticated(Request::post(&url).form(form)))
+            .await?;
+
+        self.decode_action(&body)
+    }
+
+    async fn get_api<T: DeserializeOwned>(
+        &self,
+        path: &str,
+        query: &[(&str, &str)],
+    ) -> Result<T, ClientError> {
+        let url = format!("{}{}", self.base_client.api_url, path);
+
+        let body = self
+            .base_client
+            .fetch(self.authenticated(Request::get(&url).query(query)))
+            .await?;
+
+        self.decode_action(&body)
+    }
+
+    async fn graphql_query<T: DeserializeOwned>(
+        &self,
+        query_hash: &str,
+        variables: &str,
+    ) -> Result<T, ClientError> {
+        let request = Request::get(&self.base_client.graphql_api_url)
+            .query(&[("query_hash", query_hash), ("variables", variables)]);
+
+        let body = self.base_client.fetch(self.authenticated(request)).await?;
+
+        self.base_client
+            .decode::<GraphQLResponse<T>>(&body, &[])
+            .map(|r| r.data)
+    }
+
+    /// Decode a private API response, its `status` is checked before the data is decoded
+    fn decode_action<T: DeserializeOwned>(&self, body: &[u8]) -> Result<T, ClientError> {
+        serde_json::from_slice::<ActionResponse<IgnoredAny>>(body)
+            .map_err(|error| Client::decode_failure(body, error))?
+            .into_result()
+            .map_err(|error| throttle::detect_in_body(body).map_or(error, Into::into))?;
+
+        self.base_client.decode(body, &["status"])
+    }
+
+    /// Upload a JPEG through `rupload_igphoto` and return its upload id
+    async fn upload_photo(&self, photo: Vec<u8>) -> Result<String, ClientError> {
+        let (width, height) = upload::jpeg_dimensions(&photo).ok_or(ClientError::InvalidPhoto)?;
+        let upload_id = upload::upload_id();
+        let entity_name = format!("fb_uploader_{upload_id}");
+
+        let rupload_params = json!({
+            "media_type": 1,
+            "upload_id": upload_id,
+            "upload_media_height": height,
+            "upload_media_width": width,
         })
+        .to_string();
+
+        let url = format!(
+            "{}/rupload_igphoto/{}",
+            self.base_client.api_url, entity_name
+        );
+
+        let request = Request::post(&url)
+            .header("x-entity-type", "image/jpeg")
+            .header("x-entity-name", &entity_name)
+            .header("x-instagram-rupload-params", &rupload_params)
+            .header("offset", "0")
+            .header("content-type", "application/octet-stream")
+            .body(photo);
+
+        let body = self.base_client.fetch(self.authenticated(request)).await?;
+
+        self.decode_action::<UploadPhotoResponse>(&body)
+            .map(|response| response.upload_id)
     }
 }
 
-#[async_trait::async_trait]
+#[async_trait]
 impl FetchUserInfos for AuthenticatedClient {
     async fn fetch_user_infos(&self, username: &str) -> Result<UserInfos, UserInfosError> {
         self.base_client.fetch_user_infos(username).await
     }
 }
+
+#[async_trait]
+impl FetchUserFeed for AuthenticatedClient {
+    async fn fetch_user_feed(
+        &self,
+        user_id: UserId,
+        options: Option<FetchUserFeedOptions<'_>>,
+    ) -> Result<UserFeed, ClientError> {
+        self.base_client.fetch_user_feed(user_id, options).await
+    }
+}
+
+#[async_trait]
+impl FetchMediaInfos for AuthenticatedClient {
+    async fn fetch_media_infos(&self, media_id: MediaId) -> Result<Media, ClientError> {
+        self.base_client.fetch_media_infos(media_id).await
+    }
+}
+
+#[async_trait]
+impl FetchMediaComments for AuthenticatedClient {
+    async fn fetch_media_comments(
+        &self,
+        media_id: MediaId,
+        options: Option<FetchMediaCommentsOptions<'_>>,
+    ) -> Result<MediaComments, ClientError> {
+        self.base_client
+            .fetch_media_comments(media_id, options)
+            .await
+    }
+}
+
+#[async_trait]
+impl DeleteMedia for AuthenticatedClient {
+    async fn delete_media(&self, media_id: MediaId) -> Result<MediaDeletion, ClientError> {
+        self.post_action(&format!("/create/{media_id}/delete/"), &[])
+            .await
+    }
+}
+
+#[async_trait]
+impl ArchiveMedia for AuthenticatedClient {
+    async fn archive_media(&self, media_id: MediaId) -> Result<MediaArchiving, ClientError> {
+        self.post_action::<EmptyResponse>(&format!("/api/v1/media/{media_id}/only_me/"), &[])
+            .await
+            .map(|_| MediaArchiving {
+                media_id,
+                archived: true,
+            })
+    }
+
+    async fn unarchive_media(&self, media_id: MediaId) -> Result<MediaArchiving, ClientError> {
+        self.post_action::<EmptyResponse>(&format!("/api/v1/media/{media_id}/undo_only_me/"), &[])
+            .await
+            .map(|_| MediaArchiving {
+                media_id,
+                archived: false,
+            })
+    }
+}
+
+#[async_trait]
+impl EditMediaCaption for AuthenticatedClient {
+    async fn edit_media_caption(
+        &self,
+        media_id: MediaId,
+        caption: &str,
+    ) -> Result<MediaCaptionEdition, ClientError> {
+        self.post_action::<EditMediaResponse>(
+            &format!("/api/v1/media/{media_id}/edit_media/"),
+            &[("caption_text", caption)],
+        )
+        .await
+        .map(|response| MediaCaptionEdition {
+            media_id,
+            caption: response.media.caption.map(|caption| caption.text),
+        })
+    }
+}
+
+#[async_trait]
+impl PostPhoto for AuthenticatedClient {
+    async fn post_photo(
+        &self,
+        photo: Photo<'_>,
+        options: Option<PostPhotoOptions<'_>>,
+    ) -> Result<Media, ClientError> {
+        let options = options.unwrap_or_default();
+
+        let photo = match photo {
+            Photo::Jpeg(bytes) => bytes,
+            Photo::Path(path) => tokio::fs::read(path)
+                .await
+                .map_err(|error| ClientError::UnableToReadPhoto(error.into()))?,
+        };
+
+        let upload_id = self.upload_photo(photo).await?;
+
+        let mut form = vec![
+            ("upload_id", upload_id),
+            ("caption", options.caption.unwrap_or_default().to_string()),
+        ];
+
+        if !options.user_tags.is_empty() {
+            let user_tags: Vec<_> = options
+                .user_tags
+                .iter()
+                .map(|tag| json!({ "user_id": tag.user_id, "position": [tag.x, tag.y] }))
+                .collect();
+
+            form.push(("usertags", json!({ "in": user_tags }).to_string()));
+        }
+
+        if let Some(location) = options.location {
+            form.push((
+                "location",
+                json!({ "name": location.name(), "facebook_places_id": location.id() }).to_string(),
+            ));
+        }
+
+        let form: Vec<(&str, &str)> = form
+            .iter()
+            .map(|(name, value)| (*name, value.as_str()))
+            .collect();
+
+        self.post_action::<ConfigureMediaResponse>("/create/configure/", &form)
+            .await
+            .map(|response| response.media.into())
+    }
+}
+
+#[async_trait]
+impl FetchTimelineFeed for AuthenticatedClient {
+    async fn fetch_timeline_feed(
+        &self,
+        options: Option<FetchTimelineFeedOptions<'_>>,
+    ) -> Result<TimelineFeed, ClientError> {
+        let variables = serde_json::to_string(&options.unwrap_or_default()).unwrap();
+
+        self.graphql_query::<TimelineFeedResponse>("c699b185975935ae2a457f24075de8c7", &variables)
+            .await
+            .map(|r| r.feed)
+    }
+}
+
+#[async_trait]
+impl FetchReelsTray for AuthenticatedClient {
+    async fn fetch_reels_tray(&self) -> Result<ReelsTray, ClientError> {
+        self.get_api("/api/v1/feed/reels_tray/", &[]).await
+    }
+}
+
+#[async_trait]
+impl FetchReelsFeed for AuthenticatedClient {
+    async fn fetch_reels_feed(&self, user_ids: &[UserId]) -> Result<Vec<Reel>, ClientError> {
+        let mut reels = Vec::with_capacity(user_ids.len());
+
+        for batch in user_ids.chunks(REELS_MEDIA_BATCH_SIZE) {
+            let reel_ids: Vec<String> = batch.iter().map(ToString::to_string).collect();
+            let query: Vec<(&str, &str)> = reel_ids
+                .iter()
+                .map(|id| ("reel_ids", id.as_str()))
+                .collect();
+
+            let mut response = self
+                .get_api::<ReelsMediaResponse>("/api/v1/feed/reels_media/", &query)
+                .await?;
+
+            reels.extend(batch.iter().filter_map(|id| response.reels.remove(id)));
+        }
+
+        Ok(reels)
+    }
+}
+
+#[async_trait]
+impl FetchHighlightReels for AuthenticatedClient {
+    async fn fetch_highlight_reels(&self, user_id: UserId) -> Result<Vec<Highlight>, ClientError> {
+        let path = format!("/api/v1/highlights/{user_id}/highlights_tray/");
+        let tray = self
+            .get_api::<HighlightsTrayResponse>(&path, &[])
+            .await?
+            .tray;
+
+        let mut highlights = Vec::with_capacity(tray.len());
+
+        for batch in tray.chunks(REELS_MEDIA_BATCH_SIZE) {
+            let query: Vec<(&str, &str)> = batch
+                .iter()
+                .map(|highlight| ("reel_ids", highlight.id.as_str()))
+                .collect();
+
+            let mut response = self
+                .get_api::<HighlightsMediaResponse>("/api/v1/feed/reels_media/", &query)
+                .await?;
+
+            highlights.extend(
+                batch
+                    .iter()
+                    .filter_map(|highlight| response.reels.remove(&highlight.id)),
+            );
+        }
+
+        Ok(highlights)
+    }
+}
+
+#[async_trait]
+impl MarkStoriesAsSeen for AuthenticatedClient {
+    async fn mark_stories_as_seen(&self, items: &[&ReelItem]) -> Result<StoriesSeen, ClientError> {
+        let now = Timestamp::now();
+
+        let mut item_ids = Vec::with_capacity(items.len());
+
+        for item in items {
+            let owner_id = item.owner_id().ok_or_else(|| {
+                ClientError::ActionFailed(format!("Unable to find the owner of story {}", item.id))
+            })?;
+
+            let owner_id = owner_id.to_string();
+            let media_id = item.pk.to_string();
+            // Instagram refuses a view happening before the story was posted
+            let seen_at = now.max(item.taken_at).to_string();
+            let taken_at = item.taken_at.to_string();
+
+            self.post_action::<EmptyResponse>(
+                "/stories/reel/seen",
+                &[
+                    ("reelMediaId", &media_id),
+                    ("reelMediaOwnerId", &owner_id),
+                    ("reelId", &owner_id),
+                    ("reelMediaTakenAt", &taken_at),
+                    ("viewSeenAt", &seen_at),
+                ],
+            )
+            .await?;
+
+            item_ids.push(item.id.clone());
+        }
+
+        Ok(StoriesSeen {
+            item_ids,
+            seen_at: now,
+        })
+    }
+}
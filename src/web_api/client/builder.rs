@@ -1,30 +1,77 @@
-use crate::web_api::Client;
+use crate::web_api::{
+    rate_limit::{RateLimiter, RateLimits},
+    retry::RetryPolicy,
+    transport::SharedTransport,
+    Client,
+};
 
+/// Configuration of a [`Client`]
+///
+/// # Examples
+///
+/// ```rust
+/// use instagram::web_api::retry::RetryPolicy;
+/// use instagram::web_api::ClientBuilder;
+///
+/// let client = ClientBuilder::new()
+///     .set_retry_policy(RetryPolicy::new())
+///     .build();
+/// ```
 pub struct ClientBuilder<'a, 'b> {
     url: &'a str,
     graphql_api_url: &'b str,
+    retry_policy: Option<RetryPolicy>,
+    rate_limits: Option<RateLimits>,
+}
+
+impl Default for ClientBuilder<'_, '_> {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl<'a, 'b> ClientBuilder<'a, 'b> {
+    #[must_use]
     pub const fn new() -> Self {
         Self {
             url: "https://www.instagram.com",
             graphql_api_url: "https://www.instagram.com/graphql/query",
+            retry_policy: None,
+            rate_limits: None,
         }
     }
 
+    #[must_use]
     pub const fn set_api_url(mut self, url: &'a str) -> Self {
         self.url = url;
 
         self
     }
 
+    #[must_use]
     pub const fn set_graphql_api_url(mut self, url: &'b str) -> Self {
         self.graphql_api_url = url;
 
         self
     }
 
+    /// Retry the requests failing on a transient error, they aren't retried by default
+    #[must_use]
+    pub const fn set_retry_policy(mut self, policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(policy);
+
+        self
+    }
+
+    /// Limit the requests of the client and its clones, they aren't limited by default
+    #[must_use]
+    pub const fn set_rate_limits(mut self, limits: RateLimits) -> Self {
+        self.rate_limits = Some(limits);
+
+        self
+    }
+
+    #[must_use]
     pub fn build(self) -> Client {
         Client {
             api_url: self.url.to_string(),
@@ -32,6 +79,11 @@ impl<'a, 'b> ClientBuilder<'a, 'b> {
             csrf_token: None,
             init_csrf_token: None,
             rollout_hash: None,
+            on_drift: None,
+            lenient: false,
+            retry_policy: self.retry_policy.unwrap_or_else(RetryPolicy::none),
+            rate_limiter: RateLimiter::new(self.rate_limits.unwrap_or_default()),
+            transport: SharedTransport::default(),
         }
     }
 }
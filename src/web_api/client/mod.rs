@@ -1,41 +1,49 @@
 use async_trait::async_trait;
 
-use headers::{HeaderMap, HeaderValue};
 use regex::Regex;
-use reqwest::header as headers;
-use reqwest::Client as HttpClient;
-use reqwest::Error as HttpError;
-use reqwest::Response as HttpResponse;
+use serde::de::DeserializeOwned;
 
 use crate::web_api::{
-    behaviour::{FetchUserFeed, FetchUserInfos},
+    behaviour::{FetchMediaComments, FetchMediaInfos, FetchUserFeed, FetchUserInfos},
     credentials::Credentials,
-    domain::{UserFeed, UserInfos},
-    error::ClientError,
-    options::FetchUserFeedOptions,
+    domain::{Media, MediaComments, MediaId, UserFeed, UserId, UserInfos},
+    download::Downloader,
+    error::{ClientError, DecodeError},
+    options::{FetchMediaCommentsOptions, FetchUserFeedOptions},
+    rate_limit::{Endpoint, RateLimiter},
     response::{
-        ApiResponse, GraphQLResponse, LoginResponse, UserFeedResponse, UserInfosError,
-        UserInfosResponse,
+        lenient, throttle, ApiResponse, GraphQLResponse, LoginResponse, MediaCommentsResponse,
+        MediaInfosResponse, UserFeedResponse, UserInfosError, UserInfosResponse,
     },
+    retry::RetryPolicy,
+    schema::{self, DriftHandler, SchemaDrift},
+    transport::{Request, Response, SharedTransport, Transport, TransportError},
 };
 
 mod authenticated;
 mod builder;
+mod session;
+pub mod upload;
 
 pub use authenticated::AuthenticatedClient;
-
-use builder::ClientBuilder;
+pub use builder::ClientBuilder;
+pub use session::Session;
 
 /// Web api entrypoint Client
 ///
 /// An Unauthenticated Web client to access the api
-#[derive(PartialEq, Debug)]
+#[derive(Clone, PartialEq, Debug)]
 pub struct Client {
     api_url: String,
     graphql_api_url: String,
     csrf_token: Option<String>,
     init_csrf_token: Option<String>,
     rollout_hash: Option<String>,
+    on_drift: Option<DriftHandler>,
+    lenient: bool,
+    retry_policy: RetryPolicy,
+    rate_limiter: RateLimiter,
+    transport: SharedTransport,
 }
 
 #[async_trait]
@@ -43,11 +51,9 @@ pub struct Client {
 impl FetchUserFeed for Client {
     async fn fetch_user_feed(
         &self,
-        user_id: &str,
-        options: Option<FetchUserFeedOptions<'_, '_>>,
+        user_id: UserId,
+        options: Option<FetchUserFeedOptions<'_>>,
     ) -> Result<UserFeed, ClientError> {
-        let client = HttpClient::new();
-
         let options = options.unwrap_or_default().set_user_id(user_id);
 
         let variables = serde_json::to_string(&options).unwrap();
@@ -57,15 +63,28 @@ impl FetchUserFeed for Client {
             ("variables", &variables),
         ];
 
-        client
-            .get(&self.graphql_api_url)
-            .query(&query)
-            .send()
-            .await?
-            .json::<GraphQLResponse<UserFeedResponse>>()
-            .await
-            .map(|r| r.data.feed)
-            .map_err(Into::into)
+        let body = self
+            .fetch(Request::get(&self.graphql_api_url).query(&query))
+            .await?;
+
+        if self.lenient {
+            let mut response: serde_json::Value = serde_json::from_slice(&body)
+                .map_err(|error| Self::decode_failure(&body, error))?;
+            let feed = response
+                .pointer_mut("/data/user/edge_owner_to_timeline_media")
+                .map(serde_json::Value::take)
+                .unwrap_or_default();
+
+            return lenient::decode_user_feed(
+                feed,
+                "data.user.edge_owner_to_timeline_media",
+                self.on_drift.as_ref(),
+            )
+            .map_err(|error| Self::decode_failure(&body, error));
+        }
+
+        self.decode::<GraphQLResponse<UserFeedResponse>>(&body, &[])
+            .map(|response| response.data.feed)
     }
 }
 
@@ -73,17 +92,89 @@ impl FetchUserFeed for Client {
 impl FetchUserInfos for Client {
     async fn fetch_user_infos(&self, username: &str) -> Result<UserInfos, UserInfosError> {
         let endpoint = format!("{}/{}", self.api_url, username);
-        let client = HttpClient::new();
-
-        client
-            .get(&endpoint)
-            .query(&[("__a", "1")])
-            .send()
-            .await?
-            .json::<ApiResponse<UserInfosResponse>>()
-            .await
-            .map(|r| r.graphql.user)
-            .map_err(Into::into)
+
+        let body = self
+            .fetch(Request::get(&endpoint).query(&[("__a", "1")]))
+            .await?;
+
+        let user = self
+            .decode::<ApiResponse<UserInfosResponse>>(&body, &[])
+            .map(|r| r.graphql.user)?;
+
+        // Fields collected by `UserInfos` itself aren't seen by the decoder
+        if let Some(ref handler) = self.on_drift {
+            for field in user.extra_fields() {
+                handler.report(&SchemaDrift::UnknownField(format!("graphql.user.{field}")));
+            }
+
+            let response: serde_json::Value = serde_json::from_slice(&body)?;
+
+            if let Some(object) = response
+                .pointer("/graphql/user")
+                .and_then(|user| user.as_object())
+            {
+                schema::report_missing_fields(object, UserInfos::FIELDS, "graphql.user", handler);
+            }
+        }
+
+        Ok(user)
+    }
+}
+
+#[async_trait]
+impl FetchMediaInfos for Client {
+    async fn fetch_media_infos(&self, media_id: MediaId) -> Result<Media, ClientError> {
+        let endpoint = format!("{}/p/{}/", self.api_url, media_id.to_shortcode());
+
+        let body = self
+            .fetch(Request::get(&endpoint).query(&[("__a", "1")]))
+            .await?;
+
+        self.decode::<ApiResponse<MediaInfosResponse>>(&body, &[])
+            .map(|r| r.graphql.shortcode_media)
+    }
+}
+
+#[async_trait]
+impl FetchMediaComments for Client {
+    async fn fetch_media_comments(
+        &self,
+        media_id: MediaId,
+        options: Option<FetchMediaCommentsOptions<'_>>,
+    ) -> Result<MediaComments, ClientError> {
+        let options = options
+            .unwrap_or_default()
+            .set_shortcode(media_id.to_shortcode());
+
+        let variables = serde_json::to_string(&options).unwrap();
+
+        let query = vec![
+            ("query_hash", "bc3296d1ce80a24b1b6e40b1e72903f5"),
+            ("variables", &variables),
+        ];
+
+        let body = self
+            .fetch(Request::get(&self.graphql_api_url).query(&query))
+            .await?;
+
+        if self.lenient {
+            let mut response: serde_json::Value = serde_json::from_slice(&body)
+                .map_err(|error| Self::decode_failure(&body, error))?;
+            let comments = response
+                .pointer_mut("/data/shortcode_media/edge_media_to_parent_comment")
+                .map(serde_json::Value::take)
+                .unwrap_or_default();
+
+            return lenient::decode_media_comments(
+                comments,
+                "data.shortcode_media.edge_media_to_parent_comment",
+                self.on_drift.as_ref(),
+            )
+            .map_err(|error| Self::decode_failure(&body, error));
+        }
+
+        self.decode::<GraphQLResponse<MediaCommentsResponse>>(&body, &[])
+            .map(|r| r.data.comments)
     }
 }
 
@@ -123,6 +214,98 @@ impl Client {
         &self.api_url
     }
 
+    /// Enable the strict mode, reporting every unknown or missing field of the responses
+    ///
+    /// Instagram changes its payloads without notice, the callback receives each field that
+    /// doesn't match the types of this library along with its path in the response.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::Client;
+    ///
+    /// let client = Client::new().strict(|drift| eprintln!("Instagram changed: {drift}"));
+    /// ```
+    #[must_use]
+    pub fn strict<F>(mut self, on_drift: F) -> Self
+    where
+        F: Fn(&SchemaDrift) + Send + Sync + 'static,
+    {
+        self.on_drift = Some(DriftHandler::new(on_drift));
+
+        self
+    }
+
+    /// Enable the lenient mode, where a page of a user feed or of comments is still returned
+    /// when some of its edges are invalid
+    ///
+    /// Medias and comments of [`FetchUserFeed::fetch_user_feed`] which can't be decoded are
+    /// skipped, they're listed along with the reason of the failure in [`UserFeed::errors`], the
+    /// ones of [`FetchMediaComments::fetch_media_comments`] in [`MediaComments::errors`].
+    /// Without it, the whole page fails. The other responses aren't affected: the home timeline
+    /// always keeps its invalid entries as
+    /// [`TimelineItem::Invalid`](crate::web_api::domain::TimelineItem::Invalid), a reel or a
+    /// media which can't be decoded is an error.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::Client;
+    ///
+    /// let client = Client::new().lenient();
+    /// ```
+    #[must_use]
+    pub const fn lenient(mut self) -> Self {
+        self.lenient = true;
+
+        self
+    }
+
+    /// Send the requests through another transport than `reqwest`
+    ///
+    /// The transport is shared with the `AuthenticatedClient` created on login.
+    ///
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::transport::ReqwestTransport;
+    /// use instagram::web_api::Client;
+    ///
+    /// let http = reqwest::Client::builder()
+    ///     .proxy(reqwest::Proxy::all("http://egress.internal:3128").expect("Invalid proxy"))
+    ///     .build()
+    ///     .expect("Unable to build the http client");
+    ///
+    /// let client = Client::new().with_transport(ReqwestTransport::from_client(http));
+    /// ```
+    #[must_use]
+    pub fn with_transport<T: Transport + 'static>(mut self, transport: T) -> Self {
+        self.transport = SharedTransport::new(transport);
+
+        self
+    }
+
+    /// Downloader fetching the files of the medias through the transport of this client
+    #[must_use]
+    pub fn downloader(&self) -> Downloader {
+        Downloader::new().with_shared_transport(self.transport.clone())
+    }
+
+    /// Decode a response body, going through the strict mode when it's enabled
+    pub(crate) fn decode<T: DeserializeOwned>(
+        &self,
+        body: &[u8],
+        known_root_fields: &[&str],
+    ) -> Result<T, ClientError> {
+        schema::decode(body, self.on_drift.as_ref(), known_root_fields)
+            .map_err(|error| Self::decode_failure(body, error))
+    }
+
+    /// Error for a body which couldn't be decoded, the rate limit it signals when it's one
+    pub(crate) fn decode_failure(body: &[u8], error: impl Into<DecodeError>) -> ClientError {
+        throttle::detect_in_body(body).map_or_else(|| ClientError::Decode(error.into()), Into::into)
+    }
+
     /// Login with provided credentials
     ///
     /// # Examples
@@ -150,92 +333,86 @@ impl Client {
     ) -> Result<AuthenticatedClient, ClientError> {
         self.init_rollout_hash().await?;
 
-        let mut headers = headers::HeaderMap::new();
-
-        headers.insert(headers::ACCEPT, HeaderValue::from_static("*/*"));
-        headers.insert(headers::ACCEPT_LANGUAGE, HeaderValue::from_static("en-US"));
-        headers.insert(headers::CONNECTION, HeaderValue::from_static("close"));
-        headers.insert(
-            headers::CONTENT_TYPE,
-            HeaderValue::from_static("application/x-www-form-urlencoded"),
-        );
-
-        let cookie = cookie::Cookie::build("ig_cb", "1")
-            .domain("www.instagram.com")
-            .path("/")
-            .secure(false)
-            .finish();
+        let mut request = Request::post(&format!("{}/accounts/login/ajax/", self.api_url))
+            .header("accept", "*/*")
+            .header("accept-language", "en-US")
+            .header("connection", "close")
+            .header(
+                "cookie",
+                &cookie::Cookie::build("ig_cb", "1")
+                    .domain("www.instagram.com")
+                    .path("/")
+                    .secure(false)
+                    .finish()
+                    .to_string(),
+            )
+            .header("x-request-with", "XMLHttpRequest")
+            .header("referer", "https://www.instagram.com")
+            .header("authority", "www.instagram.com")
+            .header("origin", "https://www.instagram.com")
+            .form(&[
+                ("username", credentials.username),
+                ("password", credentials.password),
+            ]);
 
-        if let Ok(cookie_header) = HeaderValue::from_str(&cookie.to_string()) {
-            headers.insert(headers::COOKIE, cookie_header);
+        if let Some(ref csrf_token) = self.csrf_token {
+            request = request.header("x-csrftoken", csrf_token);
         }
 
-        if let Some(ref csrf_token) = self.csrf_token {
-            if let Ok(csrftoken) = HeaderValue::from_str(csrf_token) {
-                headers.insert(headers::HeaderName::from_static("x-csrftoken"), csrftoken);
-            }
+        if let Some(ref rollout_hash) = self.rollout_hash {
+            request = request.header("x-instagram-ajax", rollout_hash);
         }
 
-        headers.insert(
-            headers::HeaderName::from_static("x-request-with"),
-            HeaderValue::from_static("XMLHttpRequest"),
-        );
+        let response = self.send(request).await?;
 
-        if let Some(ref rollout_hash) = self.rollout_hash {
-            if let Ok(rollout_hash) = HeaderValue::from_str(rollout_hash) {
-                headers.insert(
-                    headers::HeaderName::from_static("x-instagram-ajax"),
-                    rollout_hash,
-                );
-            }
+        if let Some(rate_limited) = throttle::detect(&response) {
+            return Err(rate_limited.into());
         }
 
-        headers.insert(
-            headers::REFERER,
-            HeaderValue::from_static("https://www.instagram.com"),
-        );
-        headers.insert(
-            headers::HeaderName::from_static("authority"),
-            HeaderValue::from_static("www.instagram.com"),
-        );
-        headers.insert(
-            headers::ORIGIN,
-            HeaderValue::from_static("https://www.instagram.com"),
-        );
-
-        let client = HttpClient::builder()
-            .gzip(true)
-            .cookie_store(true)
-            .default_headers(headers)
-            .build()
-            .unwrap();
+        let session_cookies = response.cookies();
 
-        let url = format!("{}/accounts/login/ajax/", self.api_url);
-        let login_res: LoginResponse = client
-            .post(&url)
-            .form(&[
-                ("username", credentials.username),
-                ("password", credentials.password),
-            ])
-            .send()
-            .await?
-            .json()
-            .await?;
+        let login_res: LoginResponse = serde_json::from_slice(&response.body)
+            .map_err(|error| Self::decode_failure(&response.body, error))?;
 
         match login_res {
             LoginResponse::Success(login_infos) => {
                 AuthenticatedClient::from_login_infos(self, &login_infos)
+                    .map(|client| client.with_session_cookies(session_cookies))
             }
             LoginResponse::TwoFactorNeeded(_) => Err(ClientError::UnableToPerform2FA),
         }
     }
 
+    /// Send the request through the transport of the client, each attempt once its budget
+    /// allows it
+    pub(crate) async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        let endpoint = Endpoint::of(&request, &self.api_url, &self.graphql_api_url);
+
+        self.retry_policy
+            .send(&self.transport, &self.rate_limiter, endpoint, request)
+            .await
+    }
+
+    /// Send the request and return the body of its response
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the response is a rate limit or a soft block.
+    pub(crate) async fn fetch(&self, request: Request) -> Result<Vec<u8>, ClientError> {
+        let response = self.send(request).await?;
+
+        throttle::check(response).map_err(Into::into)
+    }
+
     // """Make a GET request to get the first csrf token and rhx_gis"""
     async fn init(&mut self) -> Result<(), ClientError> {
-        let response = self
-            .make_request(&format!("{}/", self.api_url), None)
-            .await?;
-        let body = response.text_with_charset("utf-8").await?;
+        let request = Request::get(&format!("{}/", self.api_url))
+            .header("accept", "*/*")
+            .header("accept-language", "en-US")
+            .header("connection", "close");
+
+        let response = self.send(request).await?;
+        let body = String::from_utf8_lossy(&response.body);
 
         lazy_static! {
             static ref RE_ROLLOUT_HASH: Regex =
@@ -268,39 +445,4 @@ impl Client {
     async fn init_rollout_hash(&mut self) -> Result<(), ClientError> {
         self.init().await
     }
-
-    async fn make_request(
-        &mut self,
-        url: &str,
-        credentials: Option<Credentials<'_>>,
-    ) -> Result<HttpResponse, HttpError> {
-        let mut headers = HeaderMap::new();
-
-        headers.insert(headers::ACCEPT, HeaderValue::from_static("*/*"));
-        headers.insert(headers::ACCEPT_LANGUAGE, HeaderValue::from_static("en-US"));
-        headers.insert(headers::CONNECTION, HeaderValue::from_static("close"));
-        headers.insert(
-            headers::CONTENT_TYPE,
-            HeaderValue::from_static("application/x-www-form-urlencoded"),
-        );
-
-        let client_builder = HttpClient::builder().gzip(true).cookie_store(true);
-
-        if let Some(credentials) = credentials {
-            let client = client_builder.default_headers(headers).build().unwrap();
-
-            client
-                .post(url)
-                .form(&[
-                    ("username", credentials.username),
-                    ("password", credentials.password),
-                ])
-                .send()
-                .await
-        } else {
-            let client = client_builder.default_headers(headers).build().unwrap();
-
-            client.get(url).send().await
-        }
-    }
 }
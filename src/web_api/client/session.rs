@@ -0,0 +1,30 @@
+use serde::{Deserialize, Serialize};
+
+use crate::web_api::domain::UserId;
+
+/// Tokens and cookies of a logged in client, to resume it without logging in again
+///
+/// The cookies give access to the account, store them like a password.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Session {
+    pub csrf_token: Option<String>,
+    pub init_csrf_token: Option<String>,
+    pub rollout_hash: Option<String>,
+    pub cookies: Vec<(String, String)>,
+}
+
+impl Session {
+    #[must_use]
+    pub fn cookie(&self, name: &str) -> Option<&str> {
+        self.cookies
+            .iter()
+            .find(|(cookie, _)| cookie == name)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Id of the logged in user, read from the `ds_user_id` cookie
+    #[must_use]
+    pub fn user_id(&self) -> Option<UserId> {
+        self.cookie("ds_user_id").and_then(|id| id.parse().ok())
+    }
+}
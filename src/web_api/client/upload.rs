@@ -0,0 +1,64 @@
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Generate an upload id the same way the web front does, from the current timestamp in milliseconds
+pub fn upload_id() -> String {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|duration| duration.as_millis())
+        .unwrap_or_default()
+        .to_string()
+}
+
+/// Read the `(width, height)` of a JPEG from its frame header
+///
+/// Returns `None` if the bytes aren't a JPEG.
+pub fn jpeg_dimensions(bytes: &[u8]) -> Option<(u16, u16)> {
+    let read_u16 = |at: usize| -> Option<u16> {
+        Some(u16::from_be_bytes([*bytes.get(at)?, *bytes.get(at + 1)?]))
+    };
+
+    if bytes.get(0..2)? != [0xFF, 0xD8] {
+        return None;
+    }
+
+    let mut cursor = 2;
+
+    loop {
+        if *bytes.get(cursor)? != 0xFF {
+            return None;
+        }
+
+        let marker = *bytes.get(cursor + 1)?;
+
+        match marker {
+            // Fill bytes before a marker
+            0xFF => cursor += 1,
+            // Markers without payload
+            0x01 | 0xD0..=0xD7 => cursor += 2,
+            // Start of frame, except DHT, JPG and DAC which share the range
+            0xC0..=0xCF if marker != 0xC4 && marker != 0xC8 && marker != 0xCC => {
+                return Some((read_u16(cursor + 7)?, read_u16(cursor + 5)?));
+            }
+            _ => cursor += 2 + usize::from(read_u16(cursor + 2)?),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::jpeg_dimensions;
+
+    #[test]
+    fn test_jpeg_dimensions() {
+        let jpeg = [
+            0xFF, 0xD8, // SOI
+            0xFF, 0xE0, 0x00, 0x04, 0x00, 0x00, // APP0 with 2 bytes of payload
+            0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x04, 0x38, 0x02, 0xD0, 0x01, 0x01, 0x11,
+            0x00, // SOF0
+        ];
+
+        assert_eq!(Some((720, 1080)), jpeg_dimensions(&jpeg));
+        assert_eq!(None, jpeg_dimensions(b"\x89PNG\r\n"));
+        assert_eq!(None, jpeg_dimensions(&jpeg[..10]));
+    }
+}
@@ -0,0 +1,31 @@
+use serde::{Deserialize, Serialize};
+
+use super::{MediaId, Timestamp};
+
+/// Confirmation returned by Instagram once a media has been deleted
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct MediaDeletion {
+    pub did_delete: bool,
+}
+
+/// Visibility of a media after an archive or unarchive action
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct MediaArchiving {
+    pub media_id: MediaId,
+    pub archived: bool,
+}
+
+/// Caption of a media as saved by Instagram after an edition
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct MediaCaptionEdition {
+    pub media_id: MediaId,
+    pub caption: Option<String>,
+}
+
+/// Stories marked as seen for the logged in user
+#[derive(Debug, PartialEq, Eq, Serialize)]
+pub struct StoriesSeen {
+    pub item_ids: Vec<String>,
+    /// Timestamp sent to Instagram as the moment the stories were seen
+    pub seen_at: Timestamp,
+}
@@ -1,4 +1,8 @@
-use super::{Media, MediaComment};
+use super::{
+    BusinessAddress, Media, MediaChild, MediaComment, MediaUserTag, ReelMediaResource, Sponsor,
+    SuggestedUser, TimelineItem,
+};
+use serde::de::Error;
 use serde::Deserialize;
 use serde::Deserializer;
 
@@ -38,6 +42,49 @@ where
     MediaLike::deserialize(deserializer).map(|a| a.count)
 }
 
+pub fn nested_count<'de, D>(deserializer: D) -> Result<i32, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Debug, Deserialize)]
+    pub struct Counter {
+        count: i32,
+    }
+
+    Counter::deserialize(deserializer).map(|counter| counter.count)
+}
+
+pub fn nested_mutual_followers<'de, D>(deserializer: D) -> Result<Vec<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Debug, Deserialize)]
+    pub struct MutualFollowerEdge {
+        node: MutualFollower,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct MutualFollower {
+        username: String,
+    }
+
+    Vec::<MutualFollowerEdge>::deserialize(deserializer)
+        .map(|edges| edges.into_iter().map(|edge| edge.node.username).collect())
+}
+
+/// The business address is sent as a JSON document inside a string
+pub fn business_address_json<'de, D>(deserializer: D) -> Result<Option<BusinessAddress>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    match Option::<String>::deserialize(deserializer)? {
+        Some(json) if !json.is_empty() => serde_json::from_str(&json)
+            .map(Some)
+            .map_err(D::Error::custom),
+        _ => Ok(None),
+    }
+}
+
 pub fn nested_media<'de, D>(deserializer: D) -> Result<Vec<Media>, D::Error>
 where
     D: Deserializer<'de>,
@@ -57,6 +104,69 @@ where
         .collect::<Vec<Media>>())
 }
 
+pub fn nested_media_children<'de, D>(deserializer: D) -> Result<Vec<MediaChild>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Debug, Deserialize)]
+    pub struct MediaChildList {
+        edges: Vec<MediaChildEdge>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct MediaChildEdge {
+        node: MediaChild,
+    }
+
+    MediaChildList::deserialize(deserializer)
+        .map(|list| list.edges.into_iter().map(|edge| edge.node).collect())
+}
+
+pub fn nested_media_user_tags<'de, D>(deserializer: D) -> Result<Vec<MediaUserTag>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Debug, Deserialize)]
+    pub struct MediaUserTagList {
+        edges: Vec<MediaUserTagEdge>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct MediaUserTagEdge {
+        node: MediaUserTag,
+    }
+
+    MediaUserTagList::deserialize(deserializer)
+        .map(|list| list.edges.into_iter().map(|edge| edge.node).collect())
+}
+
+pub fn nested_media_sponsors<'de, D>(deserializer: D) -> Result<Vec<Sponsor>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Debug, Deserialize)]
+    pub struct SponsorList {
+        edges: Vec<SponsorEdge>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SponsorEdge {
+        node: SponsorNode,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SponsorNode {
+        sponsor: Sponsor,
+    }
+
+    SponsorList::deserialize(deserializer).map(|list| {
+        list.edges
+            .into_iter()
+            .map(|edge| edge.node.sponsor)
+            .collect()
+    })
+}
+
 pub fn nested_media_comment<'de, D>(deserializer: D) -> Result<Vec<MediaComment>, D::Error>
 where
     D: Deserializer<'de>,
@@ -75,3 +185,57 @@ where
         .map(|Wrapper(a)| a.node)
         .collect::<Vec<MediaComment>>())
 }
+
+pub fn nested_timeline_items<'de, D>(deserializer: D) -> Result<Vec<TimelineItem>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct Wrapper(TimelineEdge);
+
+    #[derive(Debug, Deserialize)]
+    pub struct TimelineEdge {
+        node: TimelineItem,
+    }
+
+    let v = Vec::deserialize(deserializer)?;
+
+    Ok(v.into_iter()
+        .map(|Wrapper(a)| a.node)
+        .collect::<Vec<TimelineItem>>())
+}
+
+pub fn nested_suggested_users<'de, D>(deserializer: D) -> Result<Vec<SuggestedUser>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Debug, Deserialize)]
+    pub struct SuggestedUserList {
+        edges: Vec<SuggestedUserEdge>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SuggestedUserEdge {
+        node: SuggestedUserNode,
+    }
+
+    #[derive(Debug, Deserialize)]
+    pub struct SuggestedUserNode {
+        user: SuggestedUser,
+    }
+
+    SuggestedUserList::deserialize(deserializer)
+        .map(|list| list.edges.into_iter().map(|edge| edge.node.user).collect())
+}
+
+pub fn nested_image_candidates<'de, D>(deserializer: D) -> Result<Vec<ReelMediaResource>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Debug, Deserialize)]
+    pub struct ImageVersions {
+        candidates: Vec<ReelMediaResource>,
+    }
+
+    ImageVersions::deserialize(deserializer).map(|versions| versions.candidates)
+}
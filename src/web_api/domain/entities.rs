@@ -0,0 +1,237 @@
+use regex::{Captures, Regex};
+use serde::Serialize;
+use std::ops::Range;
+
+/// Longest username accepted by Instagram
+const MAX_USERNAME_LENGTH: usize = 30;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EntityKind {
+    Hashtag,
+    Mention,
+    Url,
+    Emoji,
+}
+
+/// Entity found in a caption or a comment
+///
+/// Offsets are given both in bytes, to slice the Rust string, and in UTF-16 code units, as
+/// counted by Instagram and JavaScript clients.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct TextEntity<'a> {
+    kind: EntityKind,
+    text: &'a str,
+    byte_range: Range<usize>,
+    utf16_range: Range<usize>,
+}
+
+impl<'a> TextEntity<'a> {
+    #[must_use]
+    pub const fn kind(&self) -> EntityKind {
+        self.kind
+    }
+
+    /// Entity as written, `#` and `@` included
+    #[must_use]
+    pub const fn text(&self) -> &'a str {
+        self.text
+    }
+
+    /// Hashtag or username without its `#` or `@`, the text itself for the other kinds
+    #[must_use]
+    pub fn value(&self) -> &'a str {
+        match self.kind {
+            EntityKind::Hashtag | EntityKind::Mention => &self.text[1..],
+            EntityKind::Url | EntityKind::Emoji => self.text,
+        }
+    }
+
+    #[must_use]
+    pub fn byte_range(&self) -> Range<usize> {
+        self.byte_range.clone()
+    }
+
+    #[must_use]
+    pub fn utf16_range(&self) -> Range<usize> {
+        self.utf16_range.clone()
+    }
+}
+
+/// Whether a hashtag or a mention may start after this character, `foo@bar.com` isn't a mention
+fn is_entity_boundary(previous: Option<char>) -> bool {
+    !matches!(previous, Some(c) if c.is_alphanumeric() || matches!(c, '_' | '&' | '#' | '@'))
+}
+
+/// Strip the punctuation ending a sentence, keeping the parentheses opened in the URL
+fn trim_url(url: &str) -> &str {
+    let mut url = url.trim_end_matches(&['.', ',', ';', ':', '!', '?', '\'', '"'][..]);
+
+    while url.ends_with(')') && url.matches(')').count() > url.matches('(').count() {
+        url = url[..url.len() - 1].trim_end_matches(&['.', ',', ';', ':', '!', '?'][..]);
+    }
+
+    url
+}
+
+/// Kind and length of the entity matched, `None` when the match isn't a valid entity
+fn classify(text: &str, captures: &Captures<'_>) -> Option<(EntityKind, usize)> {
+    let matched = captures.get(0)?;
+    let previous = text[..matched.start()].chars().next_back();
+
+    if captures.name("url").is_some() {
+        let url = trim_url(matched.as_str());
+
+        return Some((EntityKind::Url, url.len())).filter(|_| url.contains('.'));
+    }
+
+    if captures.name("emoji").is_some() {
+        return Some((EntityKind::Emoji, matched.as_str().len()));
+    }
+
+    if !is_entity_boundary(previous) {
+        return None;
+    }
+
+    if captures.name("hashtag").is_some() {
+        let is_numeric = matched.as_str()[1..].chars().all(char::is_numeric);
+
+        return Some((EntityKind::Hashtag, matched.as_str().len())).filter(|_| !is_numeric);
+    }
+
+    let mention = matched.as_str().trim_end_matches('.');
+    let length = mention.len() - 1;
+
+    Some((EntityKind::Mention, mention.len()))
+        .filter(|_| (1..=MAX_USERNAME_LENGTH).contains(&length))
+}
+
+/// Extract the hashtags, mentions, URLs and emoji of a caption or a comment, in order
+///
+/// ```
+/// use instagram::web_api::domain::{parse_entities, EntityKind};
+///
+/// let entities = parse_entities("Shooting with @linformelle 📷 #bnwportrait");
+///
+/// assert_eq!(entities[0].kind(), EntityKind::Mention);
+/// assert_eq!(entities[0].value(), "linformelle");
+/// assert_eq!(entities[1].kind(), EntityKind::Emoji);
+/// assert_eq!(entities[2].byte_range(), 32..44);
+/// assert_eq!(entities[2].utf16_range(), 30..42);
+/// ```
+#[must_use]
+pub fn parse_entities(text: &str) -> Vec<TextEntity<'_>> {
+    lazy_static! {
+        static ref RE_ENTITY: Regex = Regex::new(concat!(
+            r"(?P<url>(?i:https?://|www\.)[^\s<>]+)",
+            r"|(?P<emoji>\p{Regional_Indicator}{2}",
+            r"|[#*0-9]\x{FE0F}?\x{20E3}",
+            r"|(?:\p{Emoji_Presentation}|\p{Extended_Pictographic}\x{FE0F})\p{Emoji_Modifier}?",
+            r"(?:\x{200D}\p{Extended_Pictographic}\x{FE0F}?\p{Emoji_Modifier}?)*)",
+            r"|(?P<hashtag>#[\p{L}\p{M}\p{N}_]+)",
+            r"|(?P<mention>@[A-Za-z0-9._]+)",
+        ))
+        .unwrap();
+    }
+
+    let mut entities = Vec::new();
+    let mut utf16_offset = 0;
+    let mut counted = 0;
+
+    for captures in RE_ENTITY.captures_iter(text) {
+        let start = captures.get(0).map_or(0, |matched| matched.start());
+
+        if let Some((kind, length)) = classify(text, &captures) {
+            let end = start + length;
+
+            utf16_offset += text[counted..start].encode_utf16().count();
+            let utf16_start = utf16_offset;
+            utf16_offset += text[start..end].encode_utf16().count();
+            counted = end;
+
+            entities.push(TextEntity {
+                kind,
+                text: &text[start..end],
+                byte_range: start..end,
+                utf16_range: utf16_start..utf16_offset,
+            });
+        }
+    }
+
+    entities
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_entities, EntityKind};
+
+    fn kinds_and_values(text: &str) -> Vec<(EntityKind, &str)> {
+        parse_entities(text)
+            .iter()
+            .map(|entity| (entity.kind(), entity.value()))
+            .collect()
+    }
+
+    #[test]
+    fn test_hashtags_and_mentions() {
+        assert_eq!(
+            kinds_and_values("Avec @linformelle !⠀\n#bnwportrait #Café_2020 #2020"),
+            vec![
+                (EntityKind::Mention, "linformelle"),
+                (EntityKind::Hashtag, "bnwportrait"),
+                (EntityKind::Hashtag, "Café_2020"),
+            ]
+        );
+        assert_eq!(
+            kinds_and_values("contact@freyskeyd.fr, merci @raphaelleflohvv."),
+            vec![(EntityKind::Mention, "raphaelleflohvv")]
+        );
+        assert!(parse_entities("&#39; a#b @").is_empty());
+    }
+
+    #[test]
+    fn test_urls() {
+        assert_eq!(
+            kinds_and_values(
+                "Portfolio: https://freyskeyd.fr/p#top. (see www.instagram.com/freyskeyd)"
+            ),
+            vec![
+                (EntityKind::Url, "https://freyskeyd.fr/p#top"),
+                (EntityKind::Url, "www.instagram.com/freyskeyd"),
+            ]
+        );
+        assert_eq!(
+            kinds_and_values("https://en.wikipedia.org/wiki/Nantes_(France)!"),
+            vec![(
+                EntityKind::Url,
+                "https://en.wikipedia.org/wiki/Nantes_(France)"
+            )]
+        );
+    }
+
+    #[test]
+    fn test_emoji() {
+        assert_eq!(
+            kinds_and_values("Très jolie ☺️ 👍🏽 👨‍👩‍👧 🇫🇷 © 1"),
+            vec![
+                (EntityKind::Emoji, "☺\u{fe0f}"),
+                (EntityKind::Emoji, "👍🏽"),
+                (EntityKind::Emoji, "👨\u{200d}👩\u{200d}👧"),
+                (EntityKind::Emoji, "🇫🇷"),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_offsets() {
+        let text = "😀 #nantes ⠀ @freyskeyd";
+        let entities = parse_entities(text);
+
+        assert_eq!(entities[0].byte_range(), 0..4);
+        assert_eq!(entities[0].utf16_range(), 0..2);
+        assert_eq!(&text[entities[1].byte_range()], "#nantes");
+        assert_eq!(entities[1].utf16_range(), 3..10);
+        assert_eq!(entities[2].byte_range(), 17..27);
+        assert_eq!(entities[2].utf16_range(), 13..23);
+    }
+}
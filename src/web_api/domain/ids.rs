@@ -0,0 +1,281 @@
+use serde::de::{self, Deserializer, Visitor};
+use serde::{Deserialize, Serialize, Serializer};
+use std::convert::TryFrom;
+use std::fmt;
+use std::str::FromStr;
+
+/// Alphabet used by Instagram to encode media ids into shortcodes
+const SHORTCODE_ALPHABET: &[u8; 64] =
+    b"ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz0123456789-_";
+
+/// Error returned when an identifier can't be parsed
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum IdError {
+    Empty,
+    InvalidCharacter(char),
+    /// The identifier doesn't fit in 64 bits
+    Overflow,
+}
+
+impl fmt::Display for IdError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Empty => write!(f, "identifier is empty"),
+            Self::InvalidCharacter(c) => write!(f, "invalid character `{c}` in identifier"),
+            Self::Overflow => write!(f, "identifier is too large"),
+        }
+    }
+}
+
+impl std::error::Error for IdError {}
+
+fn parse_numeric_id(id: &str) -> Result<u64, IdError> {
+    if id.is_empty() {
+        return Err(IdError::Empty);
+    }
+
+    if let Some(c) = id.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(IdError::InvalidCharacter(c));
+    }
+
+    id.parse().map_err(|_| IdError::Overflow)
+}
+
+macro_rules! numeric_id {
+    ($(#[$meta:meta])* $name:ident) => {
+        $(#[$meta])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, PartialOrd, Ord)]
+        pub struct $name(u64);
+
+        impl $name {
+            #[must_use]
+            pub const fn new(id: u64) -> Self {
+                Self(id)
+            }
+
+            #[must_use]
+            pub const fn as_u64(self) -> u64 {
+                self.0
+            }
+        }
+
+        impl From<u64> for $name {
+            fn from(id: u64) -> Self {
+                Self(id)
+            }
+        }
+
+        impl FromStr for $name {
+            type Err = IdError;
+
+            fn from_str(id: &str) -> Result<Self, Self::Err> {
+                parse_numeric_id(id).map(Self)
+            }
+        }
+
+        impl fmt::Display for $name {
+            fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                self.0.fmt(f)
+            }
+        }
+
+        /// Serialized as a string, like Instagram does
+        impl Serialize for $name {
+            fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+                serializer.collect_str(self)
+            }
+        }
+
+        /// Instagram sends ids either as strings or as numbers
+        impl<'de> Deserialize<'de> for $name {
+            fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+                struct IdVisitor;
+
+                impl<'de> Visitor<'de> for IdVisitor {
+                    type Value = $name;
+
+                    fn expecting(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+                        f.write_str("a numeric identifier")
+                    }
+
+                    fn visit_u64<E: de::Error>(self, id: u64) -> Result<Self::Value, E> {
+                        Ok($name(id))
+                    }
+
+                    fn visit_i64<E: de::Error>(self, id: i64) -> Result<Self::Value, E> {
+                        u64::try_from(id)
+                            .map($name)
+                            .map_err(|_| E::custom(IdError::InvalidCharacter('-')))
+                    }
+
+                    fn visit_str<E: de::Error>(self, id: &str) -> Result<Self::Value, E> {
+                        id.parse().map_err(E::custom)
+                    }
+                }
+
+                deserializer.deserialize_any(IdVisitor)
+            }
+        }
+    };
+}
+
+numeric_id!(
+    /// Identifier of an Instagram account
+    UserId
+);
+
+numeric_id!(
+    /// Identifier of a post or a story, also known as `pk`
+    MediaId
+);
+
+numeric_id!(
+    /// Identifier of a comment
+    CommentId
+);
+
+impl MediaId {
+    /// Encode the id into the shortcode used in post urls
+    #[must_use]
+    pub fn to_shortcode(self) -> Shortcode {
+        let mut id = self.0;
+        let mut code = Vec::new();
+
+        loop {
+            code.push(SHORTCODE_ALPHABET[(id % 64) as usize]);
+            id /= 64;
+
+            if id == 0 {
+                break;
+            }
+        }
+
+        code.reverse();
+
+        Shortcode(String::from_utf8(code).unwrap_or_default())
+    }
+}
+
+/// Code identifying a post in its url, as in `https://www.instagram.com/p/{shortcode}/`
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord, Serialize)]
+pub struct Shortcode(String);
+
+impl Shortcode {
+    #[must_use]
+    pub fn as_str(&self) -> &str {
+        &self.0
+    }
+
+    /// Decode the media id the shortcode stands for
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the shortcode encodes a value larger than 64 bits, which happens with
+    /// the longer codes of private posts.
+    pub fn to_media_id(&self) -> Result<MediaId, IdError> {
+        self.0
+            .bytes()
+            .try_fold(0_u64, |id, c| {
+                let position = SHORTCODE_ALPHABET
+                    .iter()
+                    .position(|&letter| letter == c)
+                    .ok_or_else(|| IdError::InvalidCharacter(char::from(c)))?;
+
+                id.checked_mul(64)
+                    .and_then(|id| id.checked_add(position as u64))
+                    .ok_or(IdError::Overflow)
+            })
+            .map(MediaId)
+    }
+}
+
+impl FromStr for Shortcode {
+    type Err = IdError;
+
+    fn from_str(code: &str) -> Result<Self, Self::Err> {
+        if code.is_empty() {
+            return Err(IdError::Empty);
+        }
+
+        if let Some(c) = code
+            .chars()
+            .find(|c| !c.is_ascii() || !SHORTCODE_ALPHABET.contains(&(*c as u8)))
+        {
+            return Err(IdError::InvalidCharacter(c));
+        }
+
+        Ok(Self(code.to_string()))
+    }
+}
+
+impl From<MediaId> for Shortcode {
+    fn from(id: MediaId) -> Self {
+        id.to_shortcode()
+    }
+}
+
+impl TryFrom<&Shortcode> for MediaId {
+    type Error = IdError;
+
+    fn try_from(code: &Shortcode) -> Result<Self, Self::Error> {
+        code.to_media_id()
+    }
+}
+
+impl fmt::Display for Shortcode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.0)
+    }
+}
+
+impl<'de> Deserialize<'de> for Shortcode {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        String::deserialize(deserializer)?
+            .parse()
+            .map_err(de::Error::custom)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{IdError, MediaId, Shortcode, UserId};
+
+    #[test]
+    fn test_shortcode_conversion() {
+        let media_id = MediaId::new(2_250_382_848_586_272_543);
+        let shortcode: Shortcode = "B8690canc8f".parse().unwrap();
+
+        assert_eq!(shortcode, media_id.to_shortcode());
+        assert_eq!(Ok(media_id), shortcode.to_media_id());
+        assert_eq!(MediaId::new(0).to_shortcode().as_str(), "A");
+    }
+
+    #[test]
+    fn test_invalid_ids() {
+        assert_eq!(Err(IdError::Empty), "".parse::<UserId>());
+        assert_eq!(Err(IdError::InvalidCharacter('a')), "12a".parse::<UserId>());
+        assert_eq!(
+            Err(IdError::Overflow),
+            "99999999999999999999".parse::<UserId>()
+        );
+        assert_eq!(
+            Err(IdError::InvalidCharacter('/')),
+            "B8690/anc8f".parse::<Shortcode>()
+        );
+        assert_eq!(
+            Err(IdError::Overflow),
+            "B8690canc8fB8690canc8f"
+                .parse::<Shortcode>()
+                .unwrap()
+                .to_media_id()
+        );
+    }
+
+    #[test]
+    fn test_ids_deserialization() {
+        let ids: Vec<UserId> = serde_json::from_str("[8999249, \"8999249\"]").unwrap();
+
+        assert_eq!(ids, vec![UserId::new(8_999_249), UserId::new(8_999_249)]);
+        assert_eq!("\"8999249\"", serde_json::to_string(&ids[0]).unwrap());
+    }
+}
@@ -1,9 +1,12 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use std::collections::HashMap;
 
+use super::{deserializer, UserFeed, UserId};
+use crate::web_api::reference::Reference;
+
 #[allow(clippy::struct_excessive_bools)]
-#[derive(Debug, Deserialize)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UserInfos {
     pub biography: String,
     pub blocked_by_viewer: bool,
@@ -19,7 +22,7 @@ pub struct UserInfos {
     pub has_blocked_viewer: bool,
     pub highlight_reel_count: i32,
     pub has_requested_viewer: bool,
-    pub id: String,
+    pub id: UserId,
     pub is_business_account: bool,
     pub is_joined_recently: bool,
     pub business_category_name: String,
@@ -33,16 +36,191 @@ pub struct UserInfos {
     pub username: String,
     pub connected_fb_page: Option<String>,
 
+    #[serde(
+        rename(deserialize = "edge_followed_by"),
+        deserialize_with = "deserializer::nested_count"
+    )]
+    pub follower_count: i32,
+    #[serde(
+        rename(deserialize = "edge_follow"),
+        deserialize_with = "deserializer::nested_count"
+    )]
+    pub following_count: i32,
+    #[serde(rename(deserialize = "edge_mutual_followed_by"), default)]
+    pub mutual_followers: MutualFollowers,
+    #[serde(flatten)]
+    pub business_contact: BusinessContact,
+    #[serde(default)]
+    pub bio_links: Vec<BioLink>,
+    #[serde(default)]
+    pub pronouns: Vec<String>,
+    /// Category of the account, as in `PHOTOGRAPHER`
+    #[serde(default)]
+    pub category_enum: Option<String>,
+    #[serde(default)]
+    pub category_name: Option<String>,
+    /// First page of the user's posts, missing when the profile is private
+    #[serde(rename(deserialize = "edge_owner_to_timeline_media"), default)]
+    pub timeline_media: Option<UserFeed>,
+
     #[serde(flatten)]
     extra: HashMap<String, Value>,
 }
 
-#[derive(Debug, PartialEq, Deserialize)]
+/// Followers of the user that the logged in user follows too
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct MutualFollowers {
+    pub count: i32,
+    /// Usernames of the first mutual followers
+    #[serde(
+        rename(deserialize = "edges"),
+        deserialize_with = "deserializer::nested_mutual_followers",
+        default
+    )]
+    pub usernames: Vec<String>,
+}
+
+/// Contact informations displayed on business and creator profiles
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct BusinessContact {
+    #[serde(default)]
+    pub business_email: Option<String>,
+    #[serde(default)]
+    pub business_phone_number: Option<String>,
+    /// Preferred way to be contacted, as in `CALL` or `TEXT`
+    #[serde(default)]
+    pub business_contact_method: Option<String>,
+    #[serde(
+        rename(deserialize = "business_address_json"),
+        deserialize_with = "deserializer::business_address_json",
+        default
+    )]
+    pub business_address: Option<BusinessAddress>,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
+pub struct BusinessAddress {
+    #[serde(default)]
+    pub street_address: Option<String>,
+    #[serde(default)]
+    pub zip_code: Option<String>,
+    #[serde(default)]
+    pub city_name: Option<String>,
+    #[serde(default)]
+    pub region_name: Option<String>,
+    #[serde(default)]
+    pub country_code: Option<String>,
+}
+
+/// Link displayed under the biography
+#[derive(Debug, Deserialize, Serialize)]
+pub struct BioLink {
+    #[serde(default)]
+    pub title: String,
+    pub url: String,
+    /// Url wrapped by Instagram's redirection service
+    #[serde(default)]
+    pub lynx_url: Option<String>,
+    #[serde(default)]
+    pub link_type: Option<String>,
+}
+
+impl UserInfos {
+    /// Keys of the response decoded into `UserInfos`, its flattened fields keep the decoder from
+    /// listing them
+    pub(crate) const FIELDS: &'static [&'static str] = &[
+        "biography",
+        "blocked_by_viewer",
+        "restricted_by_viewer",
+        "country_block",
+        "external_url",
+        "external_url_linkshimmed",
+        "followed_by_viewer",
+        "follows_viewer",
+        "full_name",
+        "has_ar_effects",
+        "has_channel",
+        "has_blocked_viewer",
+        "highlight_reel_count",
+        "has_requested_viewer",
+        "id",
+        "is_business_account",
+        "is_joined_recently",
+        "business_category_name",
+        "category_id",
+        "overall_category_name",
+        "is_private",
+        "is_verified",
+        "profile_pic_url",
+        "profile_pic_url_hd",
+        "requested_by_viewer",
+        "username",
+        "connected_fb_page",
+        "edge_followed_by",
+        "edge_follow",
+        "edge_mutual_followed_by",
+        "business_email",
+        "business_phone_number",
+        "business_contact_method",
+        "business_address_json",
+        "bio_links",
+        "pronouns",
+        "category_enum",
+        "category_name",
+        "edge_owner_to_timeline_media",
+    ];
+
+    /// Fields of the response which aren't decoded yet
+    pub(crate) fn extra_fields(&self) -> impl Iterator<Item = &str> {
+        self.extra.keys().map(String::as_str)
+    }
+
+    /// Number of posts published by the user
+    #[must_use]
+    pub fn post_count(&self) -> Option<i32> {
+        self.timeline_media.as_ref().map(|feed| feed.count)
+    }
+
+    /// Canonical url of the profile
+    #[must_use]
+    pub fn url(&self) -> String {
+        Reference::from(self).to_url()
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "camelCase")]
 pub struct LoginInfos {
     authenticated: bool,
     user: bool,
-    user_id: String,
+    user_id: UserId,
     one_tap_prompt: bool,
     status: String,
 }
+
+impl LoginInfos {
+    #[must_use]
+    pub const fn authenticated(&self) -> bool {
+        self.authenticated
+    }
+
+    #[must_use]
+    pub const fn user(&self) -> bool {
+        self.user
+    }
+
+    #[must_use]
+    pub const fn user_id(&self) -> UserId {
+        self.user_id
+    }
+
+    #[must_use]
+    pub const fn one_tap_prompt(&self) -> bool {
+        self.one_tap_prompt
+    }
+
+    #[must_use]
+    pub fn status(&self) -> &str {
+        &self.status
+    }
+}
@@ -0,0 +1,881 @@
+use serde::{Deserialize, Serialize};
+
+use super::{
+    deserializer, parse_entities, CommentId, EdgeError, EntityKind, MediaId, PaginationInfos,
+    Shortcode, TextEntity, Timestamp, UserId,
+};
+use crate::web_api::reference::Reference;
+use crate::web_api::response::ConfiguredMedia;
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaDimensions {
+    height: i32,
+    width: i32,
+}
+
+impl MediaDimensions {
+    #[must_use]
+    pub const fn height(&self) -> i32 {
+        self.height
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> i32 {
+        self.width
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaComments {
+    count: i32,
+    // Profile pages only give the count
+    #[serde(
+        rename(deserialize = "edges", serialize = "comments"),
+        deserialize_with = "deserializer::nested_media_comment",
+        default
+    )]
+    data: Vec<MediaComment>,
+    #[serde(rename(deserialize = "page_info"), default)]
+    pagination_infos: PaginationInfos,
+    /// Comments skipped by a lenient client because they couldn't be decoded
+    #[serde(skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub(crate) errors: Vec<EdgeError>,
+}
+
+impl MediaComments {
+    /// Total number of comments on the media, not only the ones of this page
+    #[must_use]
+    pub const fn count(&self) -> i32 {
+        self.count
+    }
+
+    #[must_use]
+    pub fn comments(&self) -> &[MediaComment] {
+        &self.data
+    }
+
+    #[must_use]
+    pub fn into_comments(self) -> Vec<MediaComment> {
+        self.data
+    }
+
+    #[must_use]
+    pub const fn pagination_infos(&self) -> &PaginationInfos {
+        &self.pagination_infos
+    }
+
+    /// Comments of the page skipped by a lenient client, with the reason of their failure
+    #[must_use]
+    pub fn errors(&self) -> &[EdgeError] {
+        &self.errors
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaComment {
+    id: CommentId,
+    created_at: Timestamp,
+    did_report_as_spam: bool,
+    text: String,
+    viewer_has_liked: bool,
+    owner: CommentUser,
+}
+
+impl MediaComment {
+    #[must_use]
+    pub const fn id(&self) -> CommentId {
+        self.id
+    }
+
+    #[must_use]
+    pub const fn created_at(&self) -> Timestamp {
+        self.created_at
+    }
+
+    #[must_use]
+    pub const fn did_report_as_spam(&self) -> bool {
+        self.did_report_as_spam
+    }
+
+    #[must_use]
+    pub fn text(&self) -> &str {
+        &self.text
+    }
+
+    /// Hashtags, mentions, URLs and emoji of the comment
+    #[must_use]
+    pub fn entities(&self) -> Vec<TextEntity<'_>> {
+        parse_entities(&self.text)
+    }
+
+    #[must_use]
+    pub const fn viewer_has_liked(&self) -> bool {
+        self.viewer_has_liked
+    }
+
+    #[must_use]
+    pub const fn owner(&self) -> &CommentUser {
+        &self.owner
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct CommentUser {
+    id: UserId,
+    is_verified: bool,
+    profile_pic_url: String,
+    username: String,
+}
+
+impl CommentUser {
+    #[must_use]
+    pub const fn id(&self) -> UserId {
+        self.id
+    }
+
+    #[must_use]
+    pub const fn is_verified(&self) -> bool {
+        self.is_verified
+    }
+
+    #[must_use]
+    pub fn profile_pic_url(&self) -> &str {
+        &self.profile_pic_url
+    }
+
+    #[must_use]
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaOwner {
+    id: UserId,
+    username: String,
+}
+
+impl MediaOwner {
+    #[must_use]
+    pub const fn id(&self) -> UserId {
+        self.id
+    }
+
+    #[must_use]
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ThumbnailResource {
+    src: String,
+    #[serde(rename(deserialize = "config_height"))]
+    height: i32,
+    #[serde(rename(deserialize = "config_width"))]
+    width: i32,
+}
+
+impl ThumbnailResource {
+    #[must_use]
+    pub fn src(&self) -> &str {
+        &self.src
+    }
+
+    #[must_use]
+    pub const fn height(&self) -> i32 {
+        self.height
+    }
+
+    #[must_use]
+    pub const fn width(&self) -> i32 {
+        self.width
+    }
+}
+
+/// Kind of a post, as displayed by Instagram
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MediaKind {
+    Image,
+    Video,
+    /// Post made of several images or videos, see [`Media::children`]
+    Carousel,
+    /// Short video published as a reel
+    Reel,
+    /// Long video published on IGTV
+    Igtv,
+}
+
+/// Image or video of a carousel
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaChild {
+    id: MediaId,
+    shortcode: Shortcode,
+    dimensions: MediaDimensions,
+    display_url: String,
+    #[serde(default)]
+    display_resources: Vec<ThumbnailResource>,
+    is_video: bool,
+    #[serde(default)]
+    video_url: Option<String>,
+    #[serde(default)]
+    video_view_count: Option<i64>,
+    #[serde(
+        rename(deserialize = "edge_media_to_tagged_user"),
+        deserialize_with = "deserializer::nested_media_user_tags",
+        default
+    )]
+    user_tags: Vec<MediaUserTag>,
+}
+
+impl MediaChild {
+    #[must_use]
+    pub const fn id(&self) -> MediaId {
+        self.id
+    }
+
+    #[must_use]
+    pub const fn shortcode(&self) -> &Shortcode {
+        &self.shortcode
+    }
+
+    /// Either [`MediaKind::Image`] or [`MediaKind::Video`]
+    #[must_use]
+    pub const fn kind(&self) -> MediaKind {
+        if self.is_video {
+            MediaKind::Video
+        } else {
+            MediaKind::Image
+        }
+    }
+
+    #[must_use]
+    pub const fn dimensions(&self) -> &MediaDimensions {
+        &self.dimensions
+    }
+
+    #[must_use]
+    pub fn display_url(&self) -> &str {
+        &self.display_url
+    }
+
+    /// Available sizes of the image, or of the video cover
+    #[must_use]
+    pub fn display_resources(&self) -> &[ThumbnailResource] {
+        &self.display_resources
+    }
+
+    #[must_use]
+    pub const fn is_video(&self) -> bool {
+        self.is_video
+    }
+
+    #[must_use]
+    pub fn video_url(&self) -> Option<&str> {
+        self.video_url.as_deref()
+    }
+
+    #[must_use]
+    pub const fn video_view_count(&self) -> Option<i64> {
+        self.video_view_count
+    }
+
+    #[must_use]
+    pub fn user_tags(&self) -> &[MediaUserTag] {
+        &self.user_tags
+    }
+}
+
+/// Account tagged on a media
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TaggedUser {
+    id: UserId,
+    username: String,
+    #[serde(default)]
+    full_name: String,
+    #[serde(default)]
+    is_verified: bool,
+    profile_pic_url: String,
+}
+
+impl TaggedUser {
+    #[must_use]
+    pub const fn id(&self) -> UserId {
+        self.id
+    }
+
+    #[must_use]
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+
+    #[must_use]
+    pub fn full_name(&self) -> &str {
+        &self.full_name
+    }
+
+    #[must_use]
+    pub const fn is_verified(&self) -> bool {
+        self.is_verified
+    }
+
+    #[must_use]
+    pub fn profile_pic_url(&self) -> &str {
+        &self.profile_pic_url
+    }
+}
+
+/// Tag of an account on a media, positioned relatively to the image
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaUserTag {
+    user: TaggedUser,
+    x: f64,
+    y: f64,
+}
+
+impl MediaUserTag {
+    #[must_use]
+    pub const fn user(&self) -> &TaggedUser {
+        &self.user
+    }
+
+    /// Horizontal position, from `0.0` (left) to `1.0` (right)
+    #[must_use]
+    pub const fn x(&self) -> f64 {
+        self.x
+    }
+
+    /// Vertical position, from `0.0` (top) to `1.0` (bottom)
+    #[must_use]
+    pub const fn y(&self) -> f64 {
+        self.y
+    }
+}
+
+/// Brand partner of a paid partnership
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Sponsor {
+    id: UserId,
+    username: String,
+}
+
+impl Sponsor {
+    #[must_use]
+    pub const fn id(&self) -> UserId {
+        self.id
+    }
+
+    #[must_use]
+    pub fn username(&self) -> &str {
+        &self.username
+    }
+}
+
+/// Notice displayed by Instagram when a post was rated by fact-checkers
+#[derive(Debug, Deserialize, Serialize)]
+pub struct FactCheck {
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    subtitle: Option<String>,
+}
+
+impl FactCheck {
+    #[must_use]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    #[must_use]
+    pub fn subtitle(&self) -> Option<&str> {
+        self.subtitle.as_deref()
+    }
+}
+
+/// Warning hiding a post until the viewer chooses to see it, as for sensitive content
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaGating {
+    #[serde(default)]
+    gating_type: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+impl MediaGating {
+    #[must_use]
+    pub fn gating_type(&self) -> Option<&str> {
+        self.gating_type.as_deref()
+    }
+
+    #[must_use]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+/// Overlay displayed on top of a post, as for false information
+#[derive(Debug, Deserialize, Serialize)]
+pub struct MediaOverlay {
+    #[serde(default)]
+    overlay_type: Option<String>,
+    #[serde(default)]
+    title: Option<String>,
+    #[serde(default)]
+    description: Option<String>,
+}
+
+impl MediaOverlay {
+    #[must_use]
+    pub fn overlay_type(&self) -> Option<&str> {
+        self.overlay_type.as_deref()
+    }
+
+    #[must_use]
+    pub fn title(&self) -> Option<&str> {
+        self.title.as_deref()
+    }
+
+    #[must_use]
+    pub fn description(&self) -> Option<&str> {
+        self.description.as_deref()
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Location {
+    id: String,
+    #[serde(default)]
+    has_public_page: bool,
+    name: String,
+    #[serde(default)]
+    slug: String,
+}
+
+impl Location {
+    #[must_use]
+    pub fn id(&self) -> &str {
+        &self.id
+    }
+
+    #[must_use]
+    pub const fn has_public_page(&self) -> bool {
+        self.has_public_page
+    }
+
+    #[must_use]
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    #[must_use]
+    pub fn slug(&self) -> &str {
+        &self.slug
+    }
+
+    /// Canonical url of the location page
+    #[must_use]
+    pub fn url(&self) -> String {
+        Reference::from(self).to_url()
+    }
+}
+
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Media {
+    id: MediaId,
+
+    #[serde(rename(deserialize = "__typename"), default)]
+    typename: String,
+
+    #[serde(default)]
+    product_type: Option<String>,
+
+    #[serde(
+        rename(deserialize = "edge_media_to_caption"),
+        deserialize_with = "deserializer::nested_media_caption"
+    )]
+    caption: Option<String>,
+
+    #[serde(
+        rename(deserialize = "edge_media_to_comment"),
+        alias = "edge_media_preview_comment"
+    )]
+    comments: MediaComments,
+
+    comments_disabled: bool,
+    dimensions: MediaDimensions,
+    display_url: String,
+
+    #[serde(default)]
+    display_resources: Vec<ThumbnailResource>,
+
+    #[serde(
+        rename(deserialize = "edge_sidecar_to_children"),
+        deserialize_with = "deserializer::nested_media_children",
+        default
+    )]
+    children: Vec<MediaChild>,
+
+    #[serde(
+        rename(deserialize = "edge_media_preview_like", serialize = "like_count"),
+        deserialize_with = "deserializer::nested_media_likes"
+    )]
+    like: i32,
+    is_video: bool,
+
+    #[serde(default)]
+    video_url: Option<String>,
+
+    #[serde(default)]
+    video_view_count: Option<i64>,
+
+    #[serde(
+        rename(deserialize = "edge_media_to_sponsor_user"),
+        deserialize_with = "deserializer::nested_media_sponsors",
+        default
+    )]
+    sponsors: Vec<Sponsor>,
+
+    #[serde(
+        rename(deserialize = "edge_media_to_tagged_user"),
+        deserialize_with = "deserializer::nested_media_user_tags",
+        default
+    )]
+    user_tags: Vec<MediaUserTag>,
+
+    #[serde(rename(deserialize = "fact_check_information"), default)]
+    fact_check: Option<FactCheck>,
+
+    #[serde(default)]
+    fact_check_overall_rating: Option<String>,
+
+    #[serde(rename(deserialize = "gating_info"), default)]
+    gating: Option<MediaGating>,
+
+    #[serde(default)]
+    location: Option<Location>,
+
+    #[serde(rename(deserialize = "media_overlay_info"), default)]
+    overlay: Option<MediaOverlay>,
+
+    media_preview: Option<String>,
+    owner: MediaOwner,
+    shortcode: Shortcode,
+    taken_at_timestamp: Timestamp,
+
+    #[serde(rename(deserialize = "thumbnail_resources"), default)]
+    thumbnails: Vec<ThumbnailResource>,
+
+    #[serde(default)]
+    thumbnail_src: String,
+
+    // Missing from the medias embedded in profile pages
+    #[serde(default)]
+    tracking_token: String,
+    #[serde(default)]
+    viewer_can_reshare: bool,
+    #[serde(default)]
+    viewer_has_liked: bool,
+    #[serde(default)]
+    viewer_has_saved: bool,
+    #[serde(default)]
+    viewer_has_saved_to_collection: bool,
+    #[serde(default)]
+    viewer_in_photo_of_you: bool,
+}
+
+impl Media {
+    #[must_use]
+    pub const fn id(&self) -> MediaId {
+        self.id
+    }
+
+    /// Kind of the post, read from its `__typename` and `product_type`
+    #[must_use]
+    pub fn kind(&self) -> MediaKind {
+        match (self.typename.as_str(), self.product_type.as_deref()) {
+            ("GraphSidecar", _) => MediaKind::Carousel,
+            (_, Some("clips")) => MediaKind::Reel,
+            (_, Some("igtv")) => MediaKind::Igtv,
+            _ if !self.children.is_empty() => MediaKind::Carousel,
+            _ if self.is_video => MediaKind::Video,
+            _ => MediaKind::Image,
+        }
+    }
+
+    #[must_use]
+    pub fn caption(&self) -> Option<&str> {
+        self.caption.as_deref()
+    }
+
+    /// Hashtags, mentions, URLs and emoji of the caption
+    #[must_use]
+    pub fn caption_entities(&self) -> Vec<TextEntity<'_>> {
+        self.caption().map(parse_entities).unwrap_or_default()
+    }
+
+    /// Hashtags of the caption, without their `#`
+    #[must_use]
+    pub fn hashtags(&self) -> Vec<&str> {
+        self.caption_values(EntityKind::Hashtag)
+    }
+
+    /// Usernames mentioned in the caption, without their `@`
+    #[must_use]
+    pub fn mentions(&self) -> Vec<&str> {
+        self.caption_values(EntityKind::Mention)
+    }
+
+    fn caption_values(&self, kind: EntityKind) -> Vec<&str> {
+        self.caption_entities()
+            .iter()
+            .filter(|entity| entity.kind() == kind)
+            .map(TextEntity::value)
+            .collect()
+    }
+
+    /// First comments of the media, as embedded in the response
+    #[must_use]
+    pub const fn comments(&self) -> &MediaComments {
+        &self.comments
+    }
+
+    #[must_use]
+    pub const fn comments_disabled(&self) -> bool {
+        self.comments_disabled
+    }
+
+    #[must_use]
+    pub const fn dimensions(&self) -> &MediaDimensions {
+        &self.dimensions
+    }
+
+    #[must_use]
+    pub fn display_url(&self) -> &str {
+        &self.display_url
+    }
+
+    /// Available sizes of the image, or of the video cover
+    #[must_use]
+    pub fn display_resources(&self) -> &[ThumbnailResource] {
+        &self.display_resources
+    }
+
+    /// Images and videos of a carousel, empty for any other kind of post
+    #[must_use]
+    pub fn children(&self) -> &[MediaChild] {
+        &self.children
+    }
+
+    #[must_use]
+    pub const fn like_count(&self) -> i32 {
+        self.like
+    }
+
+    #[must_use]
+    pub const fn is_video(&self) -> bool {
+        self.is_video
+    }
+
+    #[must_use]
+    pub fn video_url(&self) -> Option<&str> {
+        self.video_url.as_deref()
+    }
+
+    #[must_use]
+    pub const fn video_view_count(&self) -> Option<i64> {
+        self.video_view_count
+    }
+
+    /// Brand partners of the post, when it's a paid partnership
+    #[must_use]
+    pub fn sponsors(&self) -> &[Sponsor] {
+        &self.sponsors
+    }
+
+    /// Whether the post is flagged as a paid partnership with at least one brand
+    #[must_use]
+    pub const fn is_paid_partnership(&self) -> bool {
+        !self.sponsors.is_empty()
+    }
+
+    /// Accounts tagged on the post, carousels hold the tags of each child on [`MediaChild`]
+    #[must_use]
+    pub fn user_tags(&self) -> &[MediaUserTag] {
+        &self.user_tags
+    }
+
+    #[must_use]
+    pub const fn fact_check(&self) -> Option<&FactCheck> {
+        self.fact_check.as_ref()
+    }
+
+    /// Rating given by fact-checkers, as in `false` or `partly_false`
+    #[must_use]
+    pub fn fact_check_overall_rating(&self) -> Option<&str> {
+        self.fact_check_overall_rating.as_deref()
+    }
+
+    #[must_use]
+    pub const fn gating(&self) -> Option<&MediaGating> {
+        self.gating.as_ref()
+    }
+
+    #[must_use]
+    pub const fn location(&self) -> Option<&Location> {
+        self.location.as_ref()
+    }
+
+    #[must_use]
+    pub const fn overlay(&self) -> Option<&MediaOverlay> {
+        self.overlay.as_ref()
+    }
+
+    /// Base64 encoded low resolution preview of the media
+    #[must_use]
+    pub fn media_preview(&self) -> Option<&str> {
+        self.media_preview.as_deref()
+    }
+
+    #[must_use]
+    pub const fn owner(&self) -> &MediaOwner {
+        &self.owner
+    }
+
+    #[must_use]
+    pub const fn shortcode(&self) -> &Shortcode {
+        &self.shortcode
+    }
+
+    #[must_use]
+    pub const fn taken_at_timestamp(&self) -> Timestamp {
+        self.taken_at_timestamp
+    }
+
+    #[must_use]
+    pub fn thumbnails(&self) -> &[ThumbnailResource] {
+        &self.thumbnails
+    }
+
+    #[must_use]
+    pub fn thumbnail_src(&self) -> &str {
+        &self.thumbnail_src
+    }
+
+    #[must_use]
+    pub fn tracking_token(&self) -> &str {
+        &self.tracking_token
+    }
+
+    #[must_use]
+    pub const fn viewer_can_reshare(&self) -> bool {
+        self.viewer_can_reshare
+    }
+
+    #[must_use]
+    pub const fn viewer_has_liked(&self) -> bool {
+        self.viewer_has_liked
+    }
+
+    #[must_use]
+    pub const fn viewer_has_saved(&self) -> bool {
+        self.viewer_has_saved
+    }
+
+    #[must_use]
+    pub const fn viewer_has_saved_to_collection(&self) -> bool {
+        self.viewer_has_saved_to_collection
+    }
+
+    #[must_use]
+    pub const fn viewer_in_photo_of_you(&self) -> bool {
+        self.viewer_in_photo_of_you
+    }
+
+    /// Canonical url of the post
+    #[must_use]
+    pub fn url(&self) -> String {
+        Reference::from(self).to_url()
+    }
+}
+
+impl From<ConfiguredMedia> for Media {
+    fn from(configured: ConfiguredMedia) -> Self {
+        let mut thumbnails: Vec<ThumbnailResource> = configured
+            .image_versions2
+            .candidates
+            .into_iter()
+            .map(|candidate| ThumbnailResource {
+                src: candidate.url,
+                height: candidate.height,
+                width: candidate.width,
+            })
+            .collect();
+
+        thumbnails.sort_by_key(|thumbnail| thumbnail.width);
+
+        let display_url = thumbnails
+            .last()
+            .map(|thumbnail| thumbnail.src.clone())
+            .unwrap_or_default();
+        let thumbnail_src = thumbnails
+            .first()
+            .map(|thumbnail| thumbnail.src.clone())
+            .unwrap_or_default();
+
+        Self {
+            id: configured.pk,
+            typename: "GraphImage".to_string(),
+            product_type: None,
+            caption: configured.caption.map(|caption| caption.text),
+            comments: MediaComments {
+                count: 0,
+                data: Vec::new(),
+                pagination_infos: PaginationInfos::default(),
+                errors: Vec::new(),
+            },
+            comments_disabled: false,
+            dimensions: MediaDimensions {
+                height: configured.original_height,
+                width: configured.original_width,
+            },
+            display_url,
+            display_resources: Vec::new(),
+            children: Vec::new(),
+            like: 0,
+            is_video: false,
+            video_url: None,
+            video_view_count: None,
+            sponsors: Vec::new(),
+            user_tags: Vec::new(),
+            fact_check: None,
+            fact_check_overall_rating: None,
+            gating: None,
+            location: None,
+            overlay: None,
+            media_preview: None,
+            owner: MediaOwner {
+                id: configured.user.pk,
+                username: configured.user.username,
+            },
+            shortcode: configured.code,
+            taken_at_timestamp: configured.taken_at,
+            thumbnails,
+            thumbnail_src,
+            tracking_token: String::new(),
+            viewer_can_reshare: true,
+            viewer_has_liked: false,
+            viewer_has_saved: false,
+            viewer_has_saved_to_collection: false,
+            viewer_in_photo_of_you: false,
+        }
+    }
+}
@@ -1,130 +1,67 @@
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
+mod actions;
 mod deserializer;
+mod entities;
+mod ids;
 mod infos;
-
+mod media;
+mod reels;
+mod timeline;
+mod timestamp;
+
+pub use actions::{MediaArchiving, MediaCaptionEdition, MediaDeletion, StoriesSeen};
+pub use entities::{parse_entities, EntityKind, TextEntity};
+pub use ids::{CommentId, IdError, MediaId, Shortcode, UserId};
 pub use infos::LoginInfos;
-pub use infos::UserInfos;
-
-#[derive(Debug, Deserialize)]
+pub use infos::{BioLink, BusinessAddress, BusinessContact, MutualFollowers, UserInfos};
+pub use media::{
+    CommentUser, FactCheck, Location, Media, MediaChild, MediaComment, MediaComments,
+    MediaDimensions, MediaGating, MediaKind, MediaOverlay, MediaOwner, MediaUserTag, Sponsor,
+    TaggedUser, ThumbnailResource,
+};
+pub use reels::{Highlight, Reel, ReelItem, ReelMediaResource, ReelUser, ReelsTray};
+pub use timeline::{SuggestedUser, SuggestedUsers, TimelineFeed, TimelineItem};
+pub use timestamp::Timestamp;
+
+#[derive(Debug, Deserialize, Serialize)]
 pub struct UserFeed {
     pub count: i32,
-    #[serde(rename = "edges", deserialize_with = "deserializer::nested_media")]
+    #[serde(
+        rename(deserialize = "edges"),
+        deserialize_with = "deserializer::nested_media"
+    )]
     pub medias: Vec<Media>,
-    #[serde(rename = "page_info")]
+    #[serde(rename(deserialize = "page_info"))]
     pub pagination_infos: PaginationInfos,
+    /// Edges skipped by a lenient client because they couldn't be decoded
+    #[serde(skip_deserializing, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<EdgeError>,
 }
 
-#[derive(Debug, Deserialize)]
+/// Edge of a page which couldn't be decoded
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct EdgeError {
+    /// Path of the edge's node in the response
+    pub path: String,
+    pub message: String,
+}
+
+#[derive(Debug, Default, Deserialize, Serialize)]
 pub struct PaginationInfos {
     end_cursor: Option<String>,
     has_next_page: bool,
 }
 
-#[derive(Debug, Deserialize)]
-pub struct MediaDimensions {
-    height: i32,
-    width: i32,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct MediaComments {
-    count: i32,
-    #[serde(
-        rename = "edges",
-        deserialize_with = "deserializer::nested_media_comment"
-    )]
-    data: Vec<MediaComment>,
-    #[serde(rename = "page_info")]
-    pagination_infos: PaginationInfos,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct MediaComment {
-    id: String,
-    created_at: i32,
-    did_report_as_spam: bool,
-    text: String,
-    viewer_has_liked: bool,
-    owner: CommentUser,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct CommentUser {
-    id: String,
-    is_verified: bool,
-    profile_pic_url: String,
-    username: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct MediaOwner {
-    id: String,
-    username: String,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct ThumbnailResource {
-    src: String,
-    #[serde(rename = "config_height")]
-    height: i32,
-    #[serde(rename = "config_width")]
-    width: i32,
-}
-
-#[derive(Debug, Deserialize)]
-pub struct Location {
-    id: String,
-    has_public_page: bool,
-    name: String,
-    slug: String,
-}
-
-#[allow(clippy::struct_excessive_bools)]
-#[derive(Debug, Deserialize)]
-pub struct Media {
-    id: String,
-
-    #[serde(
-        rename = "edge_media_to_caption",
-        deserialize_with = "deserializer::nested_media_caption"
-    )]
-    caption: Option<String>,
-
-    #[serde(rename = "edge_media_to_comment")]
-    comments: MediaComments,
-
-    comments_disabled: bool,
-    dimensions: MediaDimensions,
-    display_url: String,
-
-    #[serde(
-        rename = "edge_media_preview_like",
-        deserialize_with = "deserializer::nested_media_likes"
-    )]
-    like: i32,
-    is_video: bool,
-    // edge_media_to_sponsor_user: {edges: []}
-    // edge_media_to_tagged_user: {edges: []}
-    // user_tags: MediaUserTags,
-    // fact_check_information: null
-    // fact_check_overall_rating: null
-    // gating_info: null
-    // location: Location,
-    // media_overlay_info: null
-    media_preview: Option<String>,
-    owner: MediaOwner,
-    shortcode: String,
-    taken_at_timestamp: i64,
-
-    #[serde(rename = "thumbnail_resources")]
-    thumbnails: Vec<ThumbnailResource>,
-
-    thumbnail_src: String,
-    tracking_token: String,
-    viewer_can_reshare: bool,
-    viewer_has_liked: bool,
-    viewer_has_saved: bool,
-    viewer_has_saved_to_collection: bool,
-    viewer_in_photo_of_you: bool,
+impl PaginationInfos {
+    /// Cursor to give to the next request to fetch the following page
+    #[must_use]
+    pub fn end_cursor(&self) -> Option<&str> {
+        self.end_cursor.as_deref()
+    }
+
+    #[must_use]
+    pub const fn has_next_page(&self) -> bool {
+        self.has_next_page
+    }
 }
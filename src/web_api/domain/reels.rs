@@ -0,0 +1,107 @@
+use serde::{Deserialize, Serialize};
+
+use super::{deserializer, MediaId, Timestamp, UserId};
+
+/// Stories of a user as returned by the reels tray and the reels feed
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Reel {
+    pub id: UserId,
+    /// Timestamp of the most recent story of the reel
+    pub latest_reel_media: Option<Timestamp>,
+    /// Timestamp of the most recent story seen by the logged in user, `0` if none were seen
+    #[serde(default)]
+    pub seen: Timestamp,
+    pub expiring_at: Option<Timestamp>,
+    pub user: ReelUser,
+    /// Stories of the reel, the tray only includes them for the first few reels
+    #[serde(default)]
+    pub items: Vec<ReelItem>,
+}
+
+impl Reel {
+    /// Whether the reel holds stories the logged in user hasn't seen yet
+    #[must_use]
+    pub const fn has_unseen_items(&self) -> bool {
+        matches!(self.latest_reel_media, Some(latest_reel_media) if latest_reel_media.as_secs() > self.seen.as_secs())
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReelUser {
+    #[serde(rename(deserialize = "pk"))]
+    pub id: UserId,
+    pub username: String,
+    #[serde(default)]
+    pub full_name: String,
+    pub profile_pic_url: String,
+    #[serde(default)]
+    pub is_verified: bool,
+}
+
+/// A single story
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReelItem {
+    /// Story id, made of the media id and the owner id (`{pk}_{owner_id}`)
+    pub id: String,
+    pub pk: MediaId,
+    pub taken_at: Timestamp,
+    pub expiring_at: Option<Timestamp>,
+    #[serde(rename(deserialize = "original_width"))]
+    pub width: i32,
+    #[serde(rename(deserialize = "original_height"))]
+    pub height: i32,
+    #[serde(
+        rename(deserialize = "image_versions2"),
+        deserialize_with = "deserializer::nested_image_candidates"
+    )]
+    pub images: Vec<ReelMediaResource>,
+    #[serde(rename(deserialize = "video_versions"), default)]
+    pub videos: Vec<ReelMediaResource>,
+}
+
+impl ReelItem {
+    /// Id of the user who posted the story, read from the item id
+    #[must_use]
+    pub fn owner_id(&self) -> Option<UserId> {
+        self.id.split('_').nth(1).and_then(|id| id.parse().ok())
+    }
+
+    #[must_use]
+    pub const fn is_video(&self) -> bool {
+        !self.videos.is_empty()
+    }
+}
+
+/// Stories kept on the profile of a user under a title
+#[derive(Debug, Deserialize, Serialize)]
+pub struct Highlight {
+    /// Highlight id, as in `highlight:17862445040107085`
+    pub id: String,
+    pub title: String,
+    pub latest_reel_media: Option<Timestamp>,
+    pub user: ReelUser,
+    /// Stories of the highlight, the tray doesn't include them
+    #[serde(default)]
+    pub items: Vec<ReelItem>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReelMediaResource {
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+}
+
+/// The logged in user's stories tray
+#[derive(Debug, Deserialize, Serialize)]
+pub struct ReelsTray {
+    #[serde(rename(deserialize = "tray"))]
+    pub reels: Vec<Reel>,
+}
+
+impl ReelsTray {
+    /// Reels holding stories the logged in user hasn't seen yet
+    pub fn unseen(&self) -> impl Iterator<Item = &Reel> {
+        self.reels.iter().filter(|reel| reel.has_unseen_items())
+    }
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Deserializer, Serialize};
+use serde_json::Value;
+
+use super::{deserializer, Media, PaginationInfos, UserId};
+
+/// Page of the logged in user's home timeline
+#[derive(Debug, Deserialize, Serialize)]
+pub struct TimelineFeed {
+    #[serde(
+        rename(deserialize = "edges"),
+        deserialize_with = "deserializer::nested_timeline_items"
+    )]
+    pub items: Vec<TimelineItem>,
+    #[serde(rename(deserialize = "page_info"))]
+    pub pagination_infos: PaginationInfos,
+}
+
+/// Entry of the home timeline
+#[derive(Debug, Serialize)]
+#[serde(tag = "kind", content = "item", rename_all = "snake_case")]
+pub enum TimelineItem {
+    /// Post of a followed account
+    Media(Media),
+    /// Post pushed by an advertiser
+    Sponsored(Media),
+    /// Unit suggesting accounts to follow
+    SuggestedUsers(SuggestedUsers),
+    /// Unit not handled by this library yet, holding its `__typename`
+    Unknown(String),
+    /// Post or unit which couldn't be decoded, kept as sent by Instagram along with the reason
+    Invalid {
+        typename: String,
+        message: String,
+        node: Value,
+    },
+}
+
+impl<'de> Deserialize<'de> for TimelineItem {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error>
+    where
+        D: Deserializer<'de>,
+    {
+        let node = Value::deserialize(deserializer)?;
+        let typename = node
+            .get("__typename")
+            .and_then(Value::as_str)
+            .unwrap_or_default()
+            .to_string();
+
+        // An invalid entry doesn't fail the whole page
+        let decoded = match typename.as_str() {
+            "GraphImage" | "GraphVideo" | "GraphSidecar" => {
+                let is_ad = node.get("is_ad").and_then(Value::as_bool) == Some(true);
+
+                Media::deserialize(&node).map(|media| {
+                    if is_ad {
+                        Self::Sponsored(media)
+                    } else {
+                        Self::Media(media)
+                    }
+                })
+            }
+            "GraphSuggestedUserFeedUnit" => {
+                SuggestedUsers::deserialize(&node).map(Self::SuggestedUsers)
+            }
+            _ => return Ok(Self::Unknown(typename)),
+        };
+
+        Ok(decoded.unwrap_or_else(|error| Self::Invalid {
+            typename,
+            message: error.to_string(),
+            node,
+        }))
+    }
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SuggestedUsers {
+    pub description: Option<String>,
+    #[serde(
+        rename(deserialize = "edge_suggested_users"),
+        deserialize_with = "deserializer::nested_suggested_users"
+    )]
+    pub users: Vec<SuggestedUser>,
+}
+
+#[derive(Debug, Deserialize, Serialize)]
+pub struct SuggestedUser {
+    pub id: UserId,
+    pub username: String,
+    pub full_name: String,
+    pub is_verified: bool,
+    pub profile_pic_url: String,
+}
@@ -0,0 +1,94 @@
+use serde::{Deserialize, Serialize};
+use std::convert::TryFrom;
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+#[cfg(feature = "chrono")]
+use chrono::{DateTime, TimeZone, Utc};
+
+/// Unix timestamp in seconds, as sent by Instagram
+///
+/// Enable the `chrono` feature to convert it into a UTC datetime.
+#[derive(
+    Debug, Clone, Copy, Default, PartialEq, Eq, Hash, PartialOrd, Ord, Deserialize, Serialize,
+)]
+#[serde(transparent)]
+pub struct Timestamp(i64);
+
+impl Timestamp {
+    #[must_use]
+    pub const fn from_secs(secs: i64) -> Self {
+        Self(secs)
+    }
+
+    #[must_use]
+    pub const fn as_secs(self) -> i64 {
+        self.0
+    }
+
+    /// Current time, as seen by the local clock
+    #[must_use]
+    pub fn now() -> Self {
+        SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .ok()
+            .and_then(|duration| i64::try_from(duration.as_secs()).ok())
+            .map(Self)
+            .unwrap_or_default()
+    }
+
+    /// Convert into a UTC datetime, `None` if the timestamp is out of `chrono`'s range
+    #[cfg(feature = "chrono")]
+    #[must_use]
+    pub fn to_datetime(self) -> Option<DateTime<Utc>> {
+        Utc.timestamp_opt(self.0, 0).single()
+    }
+}
+
+impl From<i64> for Timestamp {
+    fn from(secs: i64) -> Self {
+        Self(secs)
+    }
+}
+
+#[cfg(feature = "chrono")]
+impl From<DateTime<Utc>> for Timestamp {
+    fn from(datetime: DateTime<Utc>) -> Self {
+        Self(datetime.timestamp())
+    }
+}
+
+impl fmt::Display for Timestamp {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Timestamp;
+
+    #[test]
+    fn test_timestamp_deserialization() {
+        let timestamps: Vec<Timestamp> = serde_json::from_str("[1582486564, 2147483648]").unwrap();
+
+        assert_eq!(timestamps[0].as_secs(), 1_582_486_564);
+        // Past the 2038 limit of 32 bits timestamps
+        assert_eq!(timestamps[1].as_secs(), 2_147_483_648);
+        assert!(timestamps[0] < timestamps[1]);
+        assert_eq!("1582486564", serde_json::to_string(&timestamps[0]).unwrap());
+    }
+
+    #[cfg(feature = "chrono")]
+    #[test]
+    fn test_timestamp_to_datetime() {
+        let datetime = Timestamp::from_secs(1_582_486_564).to_datetime().unwrap();
+
+        assert_eq!(datetime.to_rfc3339(), "2020-02-23T19:36:04+00:00");
+        assert_eq!(
+            Timestamp::from(datetime),
+            Timestamp::from_secs(1_582_486_564)
+        );
+        assert_eq!(Timestamp::from_secs(i64::MAX).to_datetime(), None);
+    }
+}
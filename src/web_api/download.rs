@@ -0,0 +1,436 @@
+use reqwest::header;
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+use std::convert::TryFrom;
+use std::path::{Path, PathBuf};
+use tokio::io::{AsyncWrite, AsyncWriteExt};
+
+use crate::web_api::{
+    domain::{Media, MediaChild, MediaDimensions, MediaId, MediaKind, ReelItem, ThumbnailResource},
+    error::ClientError,
+    transport::{Request, SharedTransport, StreamingResponse, Transport},
+};
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ResourceKind {
+    Image,
+    Video,
+}
+
+/// File Instagram serves for a media, at a given size
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Resource<'a> {
+    pub url: &'a str,
+    pub width: i32,
+    pub height: i32,
+    pub kind: ResourceKind,
+}
+
+impl<'a> Resource<'a> {
+    const fn image(url: &'a str, width: i32, height: i32) -> Self {
+        Self {
+            url,
+            width,
+            height,
+            kind: ResourceKind::Image,
+        }
+    }
+
+    const fn video(url: &'a str, width: i32, height: i32) -> Self {
+        Self {
+            url,
+            width,
+            height,
+            kind: ResourceKind::Video,
+        }
+    }
+
+    const fn area(&self) -> i64 {
+        self.width as i64 * self.height as i64
+    }
+}
+
+/// Size of the resource picked among the ones of an item
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Size {
+    /// The largest resource
+    #[default]
+    Best,
+    /// The smallest resource, usually a thumbnail
+    Smallest,
+    /// The smallest resource at least this wide, the largest one when none is wide enough
+    AtLeast(i32),
+}
+
+/// Single file of a media: the media itself, or one of the children of a carousel
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DownloadItem<'a> {
+    pub id: MediaId,
+    pub resources: Vec<Resource<'a>>,
+}
+
+impl<'a> DownloadItem<'a> {
+    const fn new(id: MediaId) -> Self {
+        Self {
+            id,
+            resources: Vec::new(),
+        }
+    }
+
+    fn push(&mut self, resource: Resource<'a>) {
+        if !resource.url.is_empty() && self.resources.iter().all(|r| r.url != resource.url) {
+            self.resources.push(resource);
+        }
+    }
+
+    fn push_display(&mut self, display_url: &'a str, dimensions: &MediaDimensions) {
+        self.push(Resource::image(
+            display_url,
+            dimensions.width(),
+            dimensions.height(),
+        ));
+    }
+
+    fn push_thumbnails(&mut self, thumbnails: &'a [ThumbnailResource]) {
+        for thumbnail in thumbnails {
+            self.push(Resource::image(
+                thumbnail.src(),
+                thumbnail.width(),
+                thumbnail.height(),
+            ));
+        }
+    }
+
+    /// Pick the resource to download, videos are preferred over their cover unless `videos`
+    /// is `false`
+    #[must_use]
+    pub fn select(&self, size: Size, videos: bool) -> Option<&Resource<'a>> {
+        let has_video = videos && self.resources.iter().any(|r| r.kind == ResourceKind::Video);
+        let kind = if has_video {
+            ResourceKind::Video
+        } else {
+            ResourceKind::Image
+        };
+        let candidates = self.resources.iter().filter(|r| r.kind == kind);
+
+        match size {
+            Size::Best => candidates.max_by_key(|r| r.area()),
+            Size::Smallest => candidates.min_by_key(|r| r.area()),
+            Size::AtLeast(width) => candidates
+                .clone()
+                .filter(|r| r.width >= width)
+                .min_by_key(|r| r.area())
+                .or_else(|| candidates.max_by_key(|r| r.area())),
+        }
+    }
+}
+
+/// Media which can be downloaded
+pub trait Downloadable {
+    /// Files making up the media, one per child of a carousel
+    fn download_items(&self) -> Vec<DownloadItem<'_>>;
+}
+
+impl Downloadable for Media {
+    fn download_items(&self) -> Vec<DownloadItem<'_>> {
+        // Profile pages don't include the children of carousels
+        if self.kind() == MediaKind::Carousel && !self.children().is_empty() {
+            return self
+                .children()
+                .iter()
+                .flat_map(Downloadable::download_items)
+                .collect();
+        }
+
+        let mut item = DownloadItem::new(self.id());
+
+        item.push_thumbnails(self.display_resources());
+        item.push_thumbnails(self.thumbnails());
+        item.push_display(self.display_url(), self.dimensions());
+
+        if let Some(video_url) = self.video_url() {
+            let dimensions = self.dimensions();
+
+            item.push(Resource::video(
+                video_url,
+                dimensions.width(),
+                dimensions.height(),
+            ));
+        }
+
+        vec![item]
+    }
+}
+
+impl Downloadable for MediaChild {
+    fn download_items(&self) -> Vec<DownloadItem<'_>> {
+        let mut item = DownloadItem::new(self.id());
+
+        item.push_thumbnails(self.display_resources());
+        item.push_display(self.display_url(), self.dimensions());
+
+        if let Some(video_url) = self.video_url() {
+            let dimensions = self.dimensions();
+
+            item.push(Resource::video(
+                video_url,
+                dimensions.width(),
+                dimensions.height(),
+            ));
+        }
+
+        vec![item]
+    }
+}
+
+impl Downloadable for ReelItem {
+    fn download_items(&self) -> Vec<DownloadItem<'_>> {
+        let mut item = DownloadItem::new(self.pk);
+
+        for image in &self.images {
+            item.push(Resource::image(&image.url, image.width, image.height));
+        }
+
+        for video in &self.videos {
+            item.push(Resource::video(&video.url, video.width, video.height));
+        }
+
+        vec![item]
+    }
+}
+
+/// File fetched by a `Downloader`
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct Download {
+    pub url: String,
+    pub content_type: Option<String>,
+    /// Extension matching the content type, or the URL when the content type is unknown
+    pub extension: &'static str,
+    /// Length of the file, in bytes
+    pub length: u64,
+    /// Hex encoded SHA-256 of the file
+    pub sha256: String,
+    /// Where the file was written, `None` when it was written to a writer
+    pub path: Option<PathBuf>,
+}
+
+/// Extension of a file, guessed from its content type and then from its URL
+fn extension(content_type: Option<&str>, url: &str) -> &'static str {
+    let from_content_type = content_type
+        .and_then(|content_type| content_type.split(';').next())
+        .map(|mime| mime.trim().to_ascii_lowercase());
+
+    let from_url = url
+        .split(&['?', '#'][..])
+        .next()
+        .and_then(|path| path.rsplit('/').next())
+        .and_then(|file| file.rsplit_once('.'))
+        .map(|(_, extension)| extension.to_ascii_lowercase());
+
+    let known = |extension: &str| match extension {
+        "image/jpeg" | "image/jpg" | "jpg" | "jpeg" => Some("jpg"),
+        "image/png" | "png" => Some("png"),
+        "image/webp" | "webp" => Some("webp"),
+        "image/heic" | "heic" => Some("heic"),
+        "image/gif" | "gif" => Some("gif"),
+        "video/mp4" | "mp4" => Some("mp4"),
+        "video/quicktime" | "mov" => Some("mov"),
+        _ => None,
+    };
+
+    from_content_type
+        .as_deref()
+        .and_then(known)
+        .or_else(|| from_url.as_deref().and_then(known))
+        .unwrap_or("bin")
+}
+
+/// Fetch the images and videos of medias and stories
+///
+/// Files are requested through a [`ReqwestTransport`](crate::web_api::transport::ReqwestTransport)
+/// unless another transport is set, [`Client::downloader`](crate::web_api::Client::downloader)
+/// returns one sending them through the transport of the client.
+///
+/// # Examples
+///
+/// ```rust
+/// use instagram::web_api::download::{Downloader, Size};
+/// # async fn doc(media: instagram::web_api::domain::Media) {
+/// let downloads = Downloader::new()
+///     .set_size(Size::AtLeast(640))
+///     .download_to_dir(&media, "photos".as_ref())
+///     .await
+///     .expect("Unable to download media");
+///
+/// for download in downloads {
+///     println!("{:?} {}", download.path, download.sha256);
+/// }
+/// # }
+/// ```
+#[derive(Debug, Clone)]
+pub struct Downloader {
+    transport: SharedTransport,
+    size: Size,
+    videos: bool,
+}
+
+impl Default for Downloader {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Downloader {
+    #[must_use]
+    pub fn new() -> Self {
+        Self {
+            transport: SharedTransport::default(),
+            size: Size::default(),
+            videos: true,
+        }
+    }
+
+    #[must_use]
+    pub const fn set_size(mut self, size: Size) -> Self {
+        self.size = size;
+
+        self
+    }
+
+    /// Download the cover image of videos instead of the videos themselves
+    #[must_use]
+    pub const fn without_videos(mut self) -> Self {
+        self.videos = false;
+
+        self
+    }
+
+    /// Send the requests through this transport, to use a proxy or an in-process Instagram
+    #[must_use]
+    pub fn with_transport<T: Transport + 'static>(self, transport: T) -> Self {
+        self.with_shared_transport(SharedTransport::new(transport))
+    }
+
+    pub(crate) fn with_shared_transport(mut self, transport: SharedTransport) -> Self {
+        self.transport = transport;
+
+        self
+    }
+
+    async fn fetch(&self, url: &str) -> Result<StreamingResponse, ClientError> {
+        let response = self.transport.send_streaming(Request::get(url)).await?;
+
+        if response.status.is_success() {
+            Ok(response)
+        } else {
+            Err(ClientError::HttpRequest)
+        }
+    }
+
+    fn content_type(response: &StreamingResponse) -> Option<&str> {
+        response
+            .headers
+            .get(header::CONTENT_TYPE)
+            .and_then(|value| value.to_str().ok())
+    }
+
+    async fn stream<W>(
+        mut response: StreamingResponse,
+        url: &str,
+        writer: &mut W,
+        path: Option<PathBuf>,
+    ) -> Result<Download, ClientError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let content_type = Self::content_type(&response).map(ToString::to_string);
+
+        let mut hasher = Sha256::new();
+        let mut length = 0;
+
+        while let Some(chunk) = response.chunk().await? {
+            hasher.update(&chunk);
+            length += u64::try_from(chunk.len()).unwrap_or(u64::MAX);
+
+            writer
+                .write_all(&chunk)
+                .await
+                .map_err(|_| ClientError::UnableToWriteDownload)?;
+        }
+
+        writer
+            .flush()
+            .await
+            .map_err(|_| ClientError::UnableToWriteDownload)?;
+
+        Ok(Download {
+            extension: extension(content_type.as_deref(), url),
+            url: url.to_string(),
+            content_type,
+            length,
+            sha256: hex::encode(hasher.finalize()),
+            path,
+        })
+    }
+
+    /// Stream a resource into a writer
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the request fails or the writer can't be written.
+    pub async fn download<W>(
+        &self,
+        resource: &Resource<'_>,
+        writer: &mut W,
+    ) -> Result<Download, ClientError>
+    where
+        W: AsyncWrite + Unpin + ?Sized,
+    {
+        let response = self.fetch(resource.url).await?;
+
+        Self::stream(response, resource.url, writer, None).await
+    }
+
+    /// Download every item of a media or a story in a directory, created when missing
+    ///
+    /// Files are named after the items ids, as in `2250382848586272543.jpg`.
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if a request fails or a file can't be written.
+    pub async fn download_to_dir<D>(
+        &self,
+        media: &D,
+        dir: &Path,
+    ) -> Result<Vec<Download>, ClientError>
+    where
+        D: Downloadable + Sync + ?Sized,
+    {
+        tokio::fs::create_dir_all(dir)
+            .await
+            .map_err(|_| ClientError::UnableToWriteDownload)?;
+
+        let mut downloads = Vec::new();
+
+        for item in media.download_items() {
+            let Some(resource) = item.select(self.size, self.videos) else {
+                continue;
+            };
+
+            let response = self.fetch(resource.url).await?;
+            let path = dir.join(format!(
+                "{}.{}",
+                item.id,
+                extension(Self::content_type(&response), resource.url)
+            ));
+
+            let mut file = tokio::fs::File::create(&path)
+                .await
+                .map_err(|_| ClientError::UnableToWriteDownload)?;
+
+            downloads.push(Self::stream(response, resource.url, &mut file, Some(path)).await?);
+        }
+
+        Ok(downloads)
+    }
+}
@@ -1,8 +1,160 @@
+use crate::web_api::transport::TransportError;
+use std::fmt;
+use std::io;
+use std::time::Duration;
+
 #[derive(PartialEq, Debug)]
 pub enum ClientError {
     UnableToPerform2FA,
     UnableToGetCsrfToken,
     HttpRequest,
+    /// The user or the media doesn't exist, or isn't visible to the viewer
+    NotFound,
+    /// The file of the photo couldn't be read
+    UnableToReadPhoto(ReadPhotoError),
+    /// The photo isn't a JPEG
+    InvalidPhoto,
+    /// Instagram answered the action with a `fail` status and the given message
+    ActionFailed(String),
+    /// A downloaded file couldn't be written
+    UnableToWriteDownload,
+    /// Instagram is throttling or blocking the requests, they should be paused
+    RateLimited(RateLimited),
+    /// The response doesn't match the types of this library
+    Decode(DecodeError),
+}
+
+/// I/O error raised while reading the file of a photo
+///
+/// Two errors are equal when they have the same kind.
+#[derive(Debug)]
+pub struct ReadPhotoError(pub(crate) io::Error);
+
+impl ReadPhotoError {
+    /// Kind of the underlying error, to tell a missing file from a denied access
+    #[must_use]
+    pub fn kind(&self) -> io::ErrorKind {
+        self.0.kind()
+    }
+
+    #[must_use]
+    pub const fn io_error(&self) -> &io::Error {
+        &self.0
+    }
+}
+
+impl PartialEq for ReadPhotoError {
+    fn eq(&self, other: &Self) -> bool {
+        self.kind() == other.kind()
+    }
+}
+
+impl fmt::Display for ReadPhotoError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Unable to read the photo: {}", self.0)
+    }
+}
+
+impl std::error::Error for ReadPhotoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        Some(&self.0)
+    }
+}
+
+impl From<io::Error> for ReadPhotoError {
+    fn from(error: io::Error) -> Self {
+        Self(error)
+    }
+}
+
+/// Response which couldn't be decoded, its schema may have drifted
+///
+/// The path uses the response keys, as in `graphql.user.edge_followed_by.count`, it's empty
+/// when the body isn't JSON at all.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DecodeError {
+    pub(crate) path: String,
+    pub(crate) message: String,
+}
+
+impl DecodeError {
+    /// Location of the failure in the response
+    #[must_use]
+    pub fn path(&self) -> &str {
+        &self.path
+    }
+
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+}
+
+impl fmt::Display for DecodeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.path.is_empty() {
+            f.write_str(&self.message)
+        } else {
+            write!(f, "{} at `{}`", self.message, self.path)
+        }
+    }
+}
+
+impl std::error::Error for DecodeError {}
+
+impl From<serde_json::Error> for DecodeError {
+    fn from(error: serde_json::Error) -> Self {
+        Self {
+            path: String::new(),
+            message: error.to_string(),
+        }
+    }
+}
+
+/// Way Instagram signaled that it's throttling or blocking the requests
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RateLimitKind {
+    /// A `429 Too Many Requests` status
+    TooManyRequests,
+    /// A "Please wait a few minutes before you try again" message
+    PleaseWait,
+    /// A `feedback_required` action block, the account can't perform this action for a while
+    FeedbackRequired,
+    /// The login page instead of the JSON response, Instagram wants a logged in session
+    LoginRedirect,
+}
+
+/// Rate limit or soft block answered by Instagram
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RateLimited {
+    pub(crate) kind: RateLimitKind,
+    pub(crate) message: String,
+    pub(crate) retry_after: Duration,
+}
+
+impl RateLimited {
+    #[must_use]
+    pub const fn kind(&self) -> RateLimitKind {
+        self.kind
+    }
+
+    /// Message sent by Instagram, or the status when there is none
+    #[must_use]
+    pub fn message(&self) -> &str {
+        &self.message
+    }
+
+    /// Suggested wait before the next request, from the `retry-after` header when it's sent
+    #[must_use]
+    pub const fn retry_after(&self) -> Duration {
+        self.retry_after
+    }
+}
+
+impl From<RateLimited> for ClientError {
+    fn from(error: RateLimited) -> Self {
+        Self::RateLimited(error)
+    }
 }
 
 impl From<reqwest::Error> for ClientError {
@@ -10,3 +162,21 @@ impl From<reqwest::Error> for ClientError {
         Self::HttpRequest
     }
 }
+
+impl From<TransportError> for ClientError {
+    fn from(_e: TransportError) -> Self {
+        Self::HttpRequest
+    }
+}
+
+impl From<DecodeError> for ClientError {
+    fn from(error: DecodeError) -> Self {
+        Self::Decode(error)
+    }
+}
+
+impl From<serde_json::Error> for ClientError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Decode(error.into())
+    }
+}
@@ -1,12 +1,23 @@
 pub(crate) mod client;
 pub use client::AuthenticatedClient;
 pub use client::Client;
+pub use client::ClientBuilder;
+pub use client::Session;
 
 mod credentials;
 pub use credentials::Credentials;
 
+pub mod archive;
+
+pub mod cassette;
+
+#[cfg(feature = "blocking")]
+pub mod blocking;
+
 pub mod domain;
 
+pub mod download;
+
 pub mod error;
 pub use error::ClientError;
 
@@ -14,4 +25,17 @@ pub mod response;
 
 pub mod options;
 
+pub mod rate_limit;
+
+pub mod reference;
+
+pub mod retry;
+
+pub mod schema;
+
+#[cfg(feature = "testing")]
+pub mod testing;
+
+pub mod transport;
+
 pub mod behaviour;
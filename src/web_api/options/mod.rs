@@ -1,5 +1,11 @@
 use serde::Serialize;
 
+use crate::web_api::domain::{Shortcode, UserId};
+
+mod post_photo;
+
+pub use post_photo::{Photo, PostPhotoOptions, UserTag};
+
 #[derive(Debug, Serialize)]
 pub struct PaginationOptions<'a> {
     #[serde(rename = "first")]
@@ -17,23 +23,39 @@ impl<'a> std::default::Default for PaginationOptions<'a> {
 }
 
 #[derive(Debug, Serialize)]
-pub struct FetchUserFeedOptions<'a, 'b> {
+pub struct FetchUserFeedOptions<'a> {
     #[serde(rename = "id")]
-    user_id: Option<&'a str>,
+    pub(crate) user_id: Option<UserId>,
 
     #[serde(flatten)]
-    pagination: PaginationOptions<'b>,
+    pub(crate) pagination: PaginationOptions<'a>,
 }
 
-impl<'a, 'b> FetchUserFeedOptions<'a, 'b> {
+impl<'a> FetchUserFeedOptions<'a> {
     #[must_use]
-    pub fn set_user_id(mut self, user_id: &'a str) -> Self {
+    pub const fn set_user_id(mut self, user_id: UserId) -> Self {
         self.user_id = Some(user_id);
 
         self
     }
+
+    /// Number of medias of the page
+    #[must_use]
+    pub const fn set_count(mut self, count: i16) -> Self {
+        self.pagination.count = count;
+
+        self
+    }
+
+    /// Fetch the page following this cursor, the `end_cursor` of the previous page
+    #[must_use]
+    pub const fn set_after(mut self, after: &'a str) -> Self {
+        self.pagination.after = Some(after);
+
+        self
+    }
 }
-impl<'a, 'b> std::default::Default for FetchUserFeedOptions<'a, 'b> {
+impl<'a> std::default::Default for FetchUserFeedOptions<'a> {
     fn default() -> Self {
         Self {
             pagination: PaginationOptions::default(),
@@ -42,6 +64,73 @@ impl<'a, 'b> std::default::Default for FetchUserFeedOptions<'a, 'b> {
     }
 }
 
+#[derive(Debug, Default, Serialize)]
+pub struct FetchMediaCommentsOptions<'a> {
+    pub(crate) shortcode: Option<Shortcode>,
+
+    #[serde(flatten)]
+    pub(crate) pagination: PaginationOptions<'a>,
+}
+
+impl<'a> FetchMediaCommentsOptions<'a> {
+    #[must_use]
+    pub fn set_shortcode(mut self, shortcode: Shortcode) -> Self {
+        self.shortcode = Some(shortcode);
+
+        self
+    }
+
+    /// Number of comments of the page
+    #[must_use]
+    pub const fn set_count(mut self, count: i16) -> Self {
+        self.pagination.count = count;
+
+        self
+    }
+
+    /// Fetch the page following this cursor, the `end_cursor` of the previous page
+    #[must_use]
+    pub const fn set_after(mut self, after: &'a str) -> Self {
+        self.pagination.after = Some(after);
+
+        self
+    }
+}
+
+#[derive(Debug, Serialize)]
+pub struct FetchTimelineFeedOptions<'a> {
+    #[serde(rename = "fetch_media_item_count")]
+    pub count: i16,
+    #[serde(rename = "fetch_media_item_cursor")]
+    pub after: Option<&'a str>,
+    #[serde(rename = "fetch_comment_count")]
+    pub comment_count: i16,
+    #[serde(rename = "fetch_like")]
+    pub like_count: i16,
+    pub has_stories: bool,
+}
+
+impl<'a> FetchTimelineFeedOptions<'a> {
+    #[must_use]
+    pub const fn set_after(mut self, after: &'a str) -> Self {
+        self.after = Some(after);
+
+        self
+    }
+}
+
+impl std::default::Default for FetchTimelineFeedOptions<'_> {
+    fn default() -> Self {
+        Self {
+            count: 12,
+            after: None,
+            comment_count: 4,
+            like_count: 3,
+            has_stories: false,
+        }
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::FetchUserFeedOptions;
@@ -0,0 +1,50 @@
+use std::path::Path;
+
+use crate::web_api::domain::{Location, UserId};
+
+/// Photo to publish, JPEG is the only format accepted by the web upload
+#[derive(Debug)]
+pub enum Photo<'a> {
+    /// Raw JPEG bytes
+    Jpeg(Vec<u8>),
+    /// Path to a JPEG file
+    Path(&'a Path),
+}
+
+/// User tagged on a photo, positions are relative to the photo size (between `0.0` and `1.0`)
+#[derive(Debug, Clone, PartialEq)]
+pub struct UserTag {
+    pub user_id: UserId,
+    pub x: f64,
+    pub y: f64,
+}
+
+#[derive(Debug, Default)]
+pub struct PostPhotoOptions<'a> {
+    pub(crate) caption: Option<&'a str>,
+    pub(crate) location: Option<&'a Location>,
+    pub(crate) user_tags: Vec<UserTag>,
+}
+
+impl<'a> PostPhotoOptions<'a> {
+    #[must_use]
+    pub const fn set_caption(mut self, caption: &'a str) -> Self {
+        self.caption = Some(caption);
+
+        self
+    }
+
+    #[must_use]
+    pub const fn set_location(mut self, location: &'a Location) -> Self {
+        self.location = Some(location);
+
+        self
+    }
+
+    #[must_use]
+    pub fn add_user_tag(mut self, user_tag: UserTag) -> Self {
+        self.user_tags.push(user_tag);
+
+        self
+    }
+}
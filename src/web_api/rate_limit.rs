@@ -0,0 +1,222 @@
+use std::collections::HashMap;
+use std::fmt;
+use std::sync::{Arc, Mutex, PoisonError};
+use std::time::{Duration, Instant};
+
+use crate::web_api::transport::Request;
+
+/// Number of requests allowed over a period, they can be sent at once
+///
+/// The budget is a token bucket holding `requests` tokens, refilled by one token every
+/// `period / requests`. A request waits for a token when the bucket is empty.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Budget {
+    requests: u32,
+    period: Duration,
+}
+
+impl Budget {
+    /// # Examples
+    ///
+    /// ```rust
+    /// use instagram::web_api::rate_limit::Budget;
+    /// use std::time::Duration;
+    ///
+    /// let budget = Budget::new(200, Duration::from_secs(3600));
+    /// ```
+    #[must_use]
+    pub fn new(requests: u32, period: Duration) -> Self {
+        Self {
+            requests: requests.max(1),
+            period,
+        }
+    }
+
+    fn refill_interval(self) -> Duration {
+        self.period / self.requests
+    }
+}
+
+/// Budgets of the requests sent by a client and its clones
+///
+/// GraphQL queries have a budget for each `query_hash`, profiles are the fetches of
+/// `UserInfos` and writes are every request changing something, login included. Requests
+/// without a budget aren't limited, which is the default. Each retry of a request takes a
+/// token too.
+///
+/// # Examples
+///
+/// ```rust
+/// use instagram::web_api::rate_limit::{Budget, RateLimits};
+/// use instagram::web_api::ClientBuilder;
+/// use std::time::Duration;
+///
+/// let client = ClientBuilder::new()
+///     .set_rate_limits(
+///         RateLimits::new()
+///             .graphql(Budget::new(60, Duration::from_secs(600)))
+///             .profile(Budget::new(20, Duration::from_secs(600)))
+///             .write(Budget::new(5, Duration::from_secs(600))),
+///     )
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub struct RateLimits {
+    graphql: Option<Budget>,
+    profile: Option<Budget>,
+    write: Option<Budget>,
+}
+
+impl RateLimits {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Budget of each GraphQL `query_hash`
+    #[must_use]
+    pub const fn graphql(mut self, budget: Budget) -> Self {
+        self.graphql = Some(budget);
+
+        self
+    }
+
+    #[must_use]
+    pub const fn profile(mut self, budget: Budget) -> Self {
+        self.profile = Some(budget);
+
+        self
+    }
+
+    #[must_use]
+    pub const fn write(mut self, budget: Budget) -> Self {
+        self.write = Some(budget);
+
+        self
+    }
+
+    const fn budget(&self, endpoint: &Endpoint) -> Option<Budget> {
+        match endpoint {
+            Endpoint::GraphQL(_) => self.graphql,
+            Endpoint::Profile => self.profile,
+            Endpoint::Write => self.write,
+        }
+    }
+}
+
+/// Kind of request, each one has its own bucket
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub(crate) enum Endpoint {
+    GraphQL(String),
+    Profile,
+    Write,
+}
+
+impl Endpoint {
+    /// Kind of a request sent by a client, `None` when it has no budget
+    pub(crate) fn of(request: &Request, api_url: &str, graphql_api_url: &str) -> Option<Self> {
+        if !request.method.is_idempotent() {
+            return Some(Self::Write);
+        }
+
+        let (path, query) = request
+            .url
+            .split_once('?')
+            .unwrap_or((request.url.as_str(), ""));
+
+        if path == graphql_api_url {
+            return serde_urlencoded::from_str::<Vec<(String, String)>>(query)
+                .ok()?
+                .into_iter()
+                .find(|(name, _)| name == "query_hash")
+                .map(|(_, query_hash)| Self::GraphQL(query_hash));
+        }
+
+        let username = path.strip_prefix(api_url)?.strip_prefix('/')?;
+
+        (!username.is_empty() && !username.contains('/') && query == "__a=1")
+            .then_some(Self::Profile)
+    }
+}
+
+#[derive(Debug)]
+struct Bucket {
+    tokens: f64,
+    updated_at: Instant,
+}
+
+impl Bucket {
+    fn new(budget: Budget) -> Self {
+        Self {
+            tokens: f64::from(budget.requests),
+            updated_at: Instant::now(),
+        }
+    }
+
+    /// Take a token, returns the time to wait until it's available
+    ///
+    /// The token is reserved even when the bucket is empty so concurrent requests are spaced
+    /// out instead of all waking up together.
+    fn take(&mut self, budget: Budget) -> Duration {
+        let now = Instant::now();
+        let interval = budget.refill_interval();
+        let refilled = now.duration_since(self.updated_at).as_secs_f64()
+            / interval.as_secs_f64().max(f64::EPSILON);
+
+        self.tokens = (self.tokens + refilled).min(f64::from(budget.requests)) - 1.0;
+        self.updated_at = now;
+
+        if self.tokens >= 0.0 {
+            Duration::default()
+        } else {
+            interval.mul_f64(-self.tokens)
+        }
+    }
+}
+
+/// Buckets of a client, shared with its clones and the authenticated clients created from it
+#[derive(Clone, Default)]
+pub(crate) struct RateLimiter {
+    limits: RateLimits,
+    buckets: Arc<Mutex<HashMap<Endpoint, Bucket>>>,
+}
+
+impl RateLimiter {
+    pub(crate) fn new(limits: RateLimits) -> Self {
+        Self {
+            limits,
+            buckets: Arc::default(),
+        }
+    }
+
+    /// Wait until the budget of the endpoint allows another request
+    pub(crate) async fn acquire(&self, endpoint: Endpoint) {
+        let Some(budget) = self.limits.budget(&endpoint) else {
+            return;
+        };
+
+        let wait = self
+            .buckets
+            .lock()
+            .unwrap_or_else(PoisonError::into_inner)
+            .entry(endpoint)
+            .or_insert_with(|| Bucket::new(budget))
+            .take(budget);
+
+        if wait > Duration::default() {
+            tokio::time::delay_for(wait).await;
+        }
+    }
+}
+
+impl fmt::Debug for RateLimiter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("RateLimiter").field(&self.limits).finish()
+    }
+}
+
+impl PartialEq for RateLimiter {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.buckets, &other.buckets)
+    }
+}
@@ -0,0 +1,350 @@
+use percent_encoding::{percent_decode_str, utf8_percent_encode, AsciiSet, NON_ALPHANUMERIC};
+use reqwest::Url;
+use std::fmt;
+use std::str::FromStr;
+
+use crate::web_api::domain::{IdError, Location, Media, MediaId, Shortcode, UserInfos};
+
+const INSTAGRAM_URL: &str = "https://www.instagram.com";
+
+/// Characters escaped in tags and slugs, the unreserved ones of RFC 3986 are kept
+const SEGMENT: &AsciiSet = &NON_ALPHANUMERIC
+    .remove(b'-')
+    .remove(b'.')
+    .remove(b'_')
+    .remove(b'~');
+
+/// First path segments that can't be usernames
+const RESERVED_PATHS: &[&str] = &[
+    "about",
+    "accounts",
+    "developer",
+    "direct",
+    "explore",
+    "legal",
+    "p",
+    "reel",
+    "reels",
+    "stories",
+    "tv",
+    "web",
+];
+
+/// A typed reference to something on Instagram, parsed from one of its urls
+///
+/// # Examples
+///
+/// ```rust
+/// use instagram::web_api::reference::Reference;
+///
+/// let reference: Reference = "https://www.instagram.com/p/B8690canc8f/?igshid=abc"
+///     .parse()
+///     .unwrap();
+///
+/// assert!(matches!(reference, Reference::Post(_)));
+/// assert_eq!(reference.to_url(), "https://www.instagram.com/p/B8690canc8f/");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Reference {
+    /// `/p/{shortcode}/`
+    Post(Shortcode),
+    /// `/reel/{shortcode}/`
+    Reel(Shortcode),
+    /// `/tv/{shortcode}/`
+    Tv(Shortcode),
+    /// `/{username}/`
+    User(String),
+    /// `/explore/tags/{tag}/`
+    Tag(String),
+    /// `/explore/locations/{id}/{slug}/`, the slug being optional
+    Location { id: String, slug: Option<String> },
+    /// `/stories/{username}/{media_id}/`
+    Story { username: String, media_id: MediaId },
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ReferenceError {
+    InvalidUrl,
+    /// The url doesn't point to instagram.com
+    NotInstagram,
+    /// The url points to a page which isn't handled
+    UnsupportedPath,
+    InvalidUsername,
+    InvalidIdentifier(IdError),
+}
+
+impl fmt::Display for ReferenceError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::InvalidUrl => write!(f, "invalid url"),
+            Self::NotInstagram => write!(f, "url doesn't point to instagram"),
+            Self::UnsupportedPath => write!(f, "unsupported instagram url"),
+            Self::InvalidUsername => write!(f, "invalid username"),
+            Self::InvalidIdentifier(e) => write!(f, "invalid identifier: {e}"),
+        }
+    }
+}
+
+impl std::error::Error for ReferenceError {}
+
+impl From<IdError> for ReferenceError {
+    fn from(e: IdError) -> Self {
+        Self::InvalidIdentifier(e)
+    }
+}
+
+fn is_valid_username(username: &str) -> bool {
+    !username.is_empty()
+        && username.len() <= 30
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '.' || c == '_')
+}
+
+fn location_id(id: &str) -> Result<String, ReferenceError> {
+    if let Some(c) = id.chars().find(|c| !c.is_ascii_digit()) {
+        return Err(IdError::InvalidCharacter(c).into());
+    }
+
+    Ok(id.to_string())
+}
+
+/// Free text segment of a path, like a tag or the slug of a location
+fn segment(segment: &str) -> Result<String, ReferenceError> {
+    percent_decode_str(segment)
+        .decode_utf8()
+        .map(std::borrow::Cow::into_owned)
+        .map_err(|_| ReferenceError::InvalidUrl)
+}
+
+fn username(username: &str) -> Result<String, ReferenceError> {
+    if is_valid_username(username) {
+        Ok(username.to_string())
+    } else {
+        Err(ReferenceError::InvalidUsername)
+    }
+}
+
+impl Reference {
+    /// Parse an Instagram url, the scheme can be omitted
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the url doesn't point to a supported Instagram page.
+    pub fn parse(url: &str) -> Result<Self, ReferenceError> {
+        let url = url.trim();
+        let url = if url.contains("://") {
+            Url::parse(url)
+        } else {
+            Url::parse(&format!("https://{url}"))
+        }
+        .map_err(|_| ReferenceError::InvalidUrl)?;
+
+        match url.host_str() {
+            Some("instagram.com" | "www.instagram.com" | "m.instagram.com" | "instagr.am") => {}
+            _ => return Err(ReferenceError::NotInstagram),
+        }
+
+        let segments: Vec<&str> = url
+            .path_segments()
+            .map(|segments| segments.filter(|s| !s.is_empty()).collect())
+            .unwrap_or_default();
+
+        match segments.as_slice() {
+            ["p", code] => Ok(Self::Post(code.parse()?)),
+            ["reel", code] => Ok(Self::Reel(code.parse()?)),
+            ["tv", code] => Ok(Self::Tv(code.parse()?)),
+            ["explore", "tags", tag] => Ok(Self::Tag(segment(tag)?)),
+            ["explore", "locations", id] => Ok(Self::Location {
+                id: location_id(id)?,
+                slug: None,
+            }),
+            ["explore", "locations", id, slug] => Ok(Self::Location {
+                id: location_id(id)?,
+                slug: Some(segment(slug)?),
+            }),
+            ["stories", "highlights", ..] => Err(ReferenceError::UnsupportedPath),
+            ["stories", user, media_id] => Ok(Self::Story {
+                username: username(user)?,
+                media_id: media_id.parse()?,
+            }),
+            [user] if !RESERVED_PATHS.contains(user) => Ok(Self::User(username(user)?)),
+            _ => Err(ReferenceError::UnsupportedPath),
+        }
+    }
+
+    /// Canonical url of the referenced page
+    #[must_use]
+    pub fn to_url(&self) -> String {
+        match self {
+            Self::Post(code) => format!("{INSTAGRAM_URL}/p/{code}/"),
+            Self::Reel(code) => format!("{INSTAGRAM_URL}/reel/{code}/"),
+            Self::Tv(code) => format!("{INSTAGRAM_URL}/tv/{code}/"),
+            Self::User(username) => format!("{INSTAGRAM_URL}/{username}/"),
+            Self::Tag(tag) => format!(
+                "{INSTAGRAM_URL}/explore/tags/{}/",
+                utf8_percent_encode(tag, SEGMENT)
+            ),
+            Self::Location {
+                id,
+                slug: Some(slug),
+            } => {
+                format!(
+                    "{INSTAGRAM_URL}/explore/locations/{id}/{}/",
+                    utf8_percent_encode(slug, SEGMENT)
+                )
+            }
+            Self::Location { id, slug: None } => format!("{INSTAGRAM_URL}/explore/locations/{id}/"),
+            Self::Story { username, media_id } => {
+                format!("{INSTAGRAM_URL}/stories/{username}/{media_id}/")
+            }
+        }
+    }
+}
+
+impl FromStr for Reference {
+    type Err = ReferenceError;
+
+    fn from_str(url: &str) -> Result<Self, Self::Err> {
+        Self::parse(url)
+    }
+}
+
+impl fmt::Display for Reference {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.to_url())
+    }
+}
+
+impl From<&Media> for Reference {
+    fn from(media: &Media) -> Self {
+        Self::Post(media.shortcode().clone())
+    }
+}
+
+impl From<&UserInfos> for Reference {
+    fn from(user: &UserInfos) -> Self {
+        Self::User(user.username.clone())
+    }
+}
+
+impl From<&Location> for Reference {
+    fn from(location: &Location) -> Self {
+        Self::Location {
+            id: location.id().to_string(),
+            slug: Some(location.slug().to_string()).filter(|slug| !slug.is_empty()),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Reference, ReferenceError};
+    use crate::web_api::domain::{IdError, MediaId};
+
+    #[test]
+    fn test_parse_references() {
+        let post: Reference = "https://www.instagram.com/p/B8690canc8f/".parse().unwrap();
+        assert_eq!(post, Reference::Post("B8690canc8f".parse().unwrap()));
+
+        assert_eq!(
+            Ok(Reference::Reel("CAbCdEf-_12".parse().unwrap())),
+            "instagram.com/reel/CAbCdEf-_12".parse()
+        );
+        assert_eq!(
+            Ok(Reference::Tv("B8690canc8f".parse().unwrap())),
+            "http://m.instagram.com/tv/B8690canc8f/?utm_source=ig_web".parse()
+        );
+        assert_eq!(
+            Ok(Reference::User("freyskeyd".into())),
+            "https://www.instagram.com/freyskeyd/?hl=fr".parse()
+        );
+        assert_eq!(
+            Ok(Reference::Tag("bnwportrait".into())),
+            "https://www.instagram.com/explore/tags/bnwportrait/".parse()
+        );
+        assert_eq!(
+            Ok(Reference::Location {
+                id: "214246727".into(),
+                slug: Some("nantes-france".into())
+            }),
+            "https://www.instagram.com/explore/locations/214246727/nantes-france/".parse()
+        );
+        assert_eq!(
+            Ok(Reference::Story {
+                username: "freyskeyd".into(),
+                media_id: MediaId::new(2_300_000_000_000_000_001)
+            }),
+            "https://www.instagram.com/stories/freyskeyd/2300000000000000001/".parse()
+        );
+    }
+
+    #[test]
+    fn test_non_ascii_references() {
+        assert_eq!(
+            Ok(Reference::Tag("café".into())),
+            "https://www.instagram.com/explore/tags/caf%C3%A9/".parse()
+        );
+        assert_eq!(
+            Ok(Reference::Tag("東京".into())),
+            "https://www.instagram.com/explore/tags/東京/".parse()
+        );
+        assert_eq!(
+            Ok(Reference::Location {
+                id: "214246727".into(),
+                slug: Some("café-de-la-gare".into())
+            }),
+            "https://www.instagram.com/explore/locations/214246727/caf%C3%A9-de-la-gare/".parse()
+        );
+        assert_eq!(
+            Reference::Tag("東京".into()).to_url(),
+            "https://www.instagram.com/explore/tags/%E6%9D%B1%E4%BA%AC/"
+        );
+        assert_eq!(
+            Err(ReferenceError::InvalidUrl),
+            "https://www.instagram.com/explore/tags/%FF/".parse::<Reference>()
+        );
+    }
+
+    #[test]
+    fn test_invalid_references() {
+        assert_eq!(
+            Err(ReferenceError::NotInstagram),
+            "https://example.com/p/B8690canc8f/".parse::<Reference>()
+        );
+        assert_eq!(
+            Err(ReferenceError::UnsupportedPath),
+            "https://www.instagram.com/accounts/login/".parse::<Reference>()
+        );
+        assert_eq!(
+            Err(ReferenceError::UnsupportedPath),
+            "https://www.instagram.com/explore/".parse::<Reference>()
+        );
+        assert_eq!(
+            Err(ReferenceError::InvalidIdentifier(
+                IdError::InvalidCharacter('!')
+            )),
+            "https://www.instagram.com/p/B86!/".parse::<Reference>()
+        );
+        assert_eq!(
+            Err(ReferenceError::InvalidUsername),
+            "https://www.instagram.com/some-user/".parse::<Reference>()
+        );
+    }
+
+    #[test]
+    fn test_canonical_urls() {
+        let urls = [
+            "https://www.instagram.com/p/B8690canc8f/",
+            "https://www.instagram.com/freyskeyd/",
+            "https://www.instagram.com/explore/locations/214246727/nantes-france/",
+            "https://www.instagram.com/stories/freyskeyd/2300000000000000001/",
+            "https://www.instagram.com/explore/tags/caf%C3%A9/",
+            "https://www.instagram.com/explore/locations/214246727/caf%C3%A9-de-la-gare/",
+        ];
+
+        for url in &urls {
+            assert_eq!(*url, url.parse::<Reference>().unwrap().to_url());
+        }
+    }
+}
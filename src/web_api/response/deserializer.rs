@@ -1,4 +1,4 @@
-use super::UserFeed;
+use super::{MediaComments, TimelineFeed, UserFeed};
 use serde::{Deserialize, Deserializer};
 
 pub fn nested_user_feed<'de, D>(deserializer: D) -> Result<UserFeed, D::Error>
@@ -12,3 +12,27 @@ where
 
     A::deserialize(deserializer).map(|a| a.edge_owner_to_timeline_media)
 }
+
+pub fn nested_timeline_feed<'de, D>(deserializer: D) -> Result<TimelineFeed, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct A {
+        edge_web_feed_timeline: TimelineFeed,
+    }
+
+    A::deserialize(deserializer).map(|a| a.edge_web_feed_timeline)
+}
+
+pub fn nested_media_comments<'de, D>(deserializer: D) -> Result<MediaComments, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    #[derive(Deserialize)]
+    struct A {
+        edge_media_to_parent_comment: MediaComments,
+    }
+
+    A::deserialize(deserializer).map(|a| a.edge_media_to_parent_comment)
+}
@@ -0,0 +1,113 @@
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::web_api::domain::{EdgeError, Media, MediaComment, MediaComments, UserFeed};
+use crate::web_api::error::DecodeError;
+use crate::web_api::schema::{self, DriftHandler};
+
+/// Keys under which a media embeds its first comments
+const COMMENT_KEYS: &[&str] = &["edge_media_to_comment", "edge_media_preview_comment"];
+
+/// Drop the comment edges which can't be decoded, recording them as errors
+///
+/// `path` locates the edges in the response.
+fn retain_valid_comment_edges(edges: &mut Vec<Value>, path: &str, errors: &mut Vec<EdgeError>) {
+    let mut index = 0;
+
+    edges.retain(|edge| {
+        let decoded = MediaComment::deserialize(&edge["node"]);
+
+        if let Err(error) = &decoded {
+            errors.push(EdgeError {
+                path: format!("{path}[{index}].node"),
+                message: error.to_string(),
+            });
+        }
+
+        index += 1;
+
+        decoded.is_ok()
+    });
+}
+
+/// Drop the comments of a media node which can't be decoded, recording them as errors
+fn retain_valid_comments(node: &mut Value, path: &str, errors: &mut Vec<EdgeError>) {
+    for key in COMMENT_KEYS {
+        let edges = node
+            .get_mut(key)
+            .and_then(|comments| comments.get_mut("edges"))
+            .and_then(Value::as_array_mut);
+
+        if let Some(edges) = edges {
+            retain_valid_comment_edges(edges, &format!("{path}.{key}.edges"), errors);
+        }
+    }
+}
+
+/// Decode a page of `edge_owner_to_timeline_media`, edges are decoded one by one
+///
+/// Medias and comments which can't be decoded are skipped and listed in `UserFeed::errors`,
+/// `path` locates the page in the response. The drifts of the decoded parts are reported to the
+/// handler when there is one.
+pub fn decode_user_feed(
+    mut feed: Value,
+    path: &str,
+    handler: Option<&DriftHandler>,
+) -> Result<UserFeed, DecodeError> {
+    let mut errors = Vec::new();
+    let mut medias = Vec::new();
+
+    let edges = match feed.get_mut("edges").map(Value::take) {
+        Some(Value::Array(edges)) => edges,
+        _ => Vec::new(),
+    };
+
+    for (index, mut edge) in edges.into_iter().enumerate() {
+        let path = format!("{path}.edges[{index}].node");
+        let mut node = edge.get_mut("node").map(Value::take).unwrap_or_default();
+
+        retain_valid_comments(&mut node, &path, &mut errors);
+
+        match schema::decode_value::<Media>(node, handler, &path) {
+            Ok(media) => medias.push(media),
+            Err(error) => errors.push(EdgeError {
+                path,
+                message: error.to_string(),
+            }),
+        }
+    }
+
+    if let Some(feed) = feed.as_object_mut() {
+        feed.insert("edges".into(), Value::Array(Vec::new()));
+    }
+
+    let mut user_feed: UserFeed = schema::decode_value(feed, handler, path)?;
+
+    user_feed.medias = medias;
+    user_feed.errors = errors;
+
+    Ok(user_feed)
+}
+
+/// Decode a page of a media's comments, edges are decoded one by one
+///
+/// Comments which can't be decoded are skipped and listed in `MediaComments::errors`, `path`
+/// locates the page in the response. The drifts of the decoded comments are reported to the
+/// handler when there is one.
+pub fn decode_media_comments(
+    mut comments: Value,
+    path: &str,
+    handler: Option<&DriftHandler>,
+) -> Result<MediaComments, DecodeError> {
+    let mut errors = Vec::new();
+
+    if let Some(edges) = comments.get_mut("edges").and_then(Value::as_array_mut) {
+        retain_valid_comment_edges(edges, &format!("{path}.edges"), &mut errors);
+    }
+
+    let mut media_comments: MediaComments = schema::decode_value(comments, handler, path)?;
+
+    media_comments.errors = errors;
+
+    Ok(media_comments)
+}
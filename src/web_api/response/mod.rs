@@ -1,10 +1,21 @@
+use crate::web_api::domain::Highlight;
 use crate::web_api::domain::LoginInfos;
+use crate::web_api::domain::Media;
+use crate::web_api::domain::MediaComments;
+use crate::web_api::domain::Reel;
+use crate::web_api::domain::TimelineFeed;
 use crate::web_api::domain::UserFeed;
 use crate::web_api::domain::UserInfos;
+use crate::web_api::domain::{MediaId, Shortcode, Timestamp, UserId};
+use crate::web_api::error::{ClientError, DecodeError, RateLimited};
+use crate::web_api::transport::TransportError;
 
 use serde::Deserialize;
+use std::collections::HashMap;
 
 mod deserializer;
+pub(crate) mod lenient;
+pub(crate) mod throttle;
 
 impl std::convert::From<reqwest::Error> for UserInfosError {
     fn from(_error: reqwest::Error) -> Self {
@@ -12,9 +23,48 @@ impl std::convert::From<reqwest::Error> for UserInfosError {
     }
 }
 
+impl std::convert::From<TransportError> for UserInfosError {
+    fn from(_error: TransportError) -> Self {
+        Self::Other
+    }
+}
+
+impl std::convert::From<serde_json::Error> for UserInfosError {
+    fn from(error: serde_json::Error) -> Self {
+        Self::Decode(error.into())
+    }
+}
+
+impl std::convert::From<DecodeError> for UserInfosError {
+    fn from(error: DecodeError) -> Self {
+        Self::Decode(error)
+    }
+}
+
+impl std::convert::From<ClientError> for UserInfosError {
+    fn from(error: ClientError) -> Self {
+        match error {
+            ClientError::NotFound => Self::NotFound,
+            ClientError::RateLimited(error) => Self::RateLimited(error),
+            ClientError::Decode(error) => Self::Decode(error),
+            _ => Self::Other,
+        }
+    }
+}
+
+impl std::convert::From<RateLimited> for UserInfosError {
+    fn from(error: RateLimited) -> Self {
+        Self::RateLimited(error)
+    }
+}
+
 #[derive(Debug)]
 pub enum UserInfosError {
     NotFound,
+    /// Instagram is throttling or blocking the requests, they should be paused
+    RateLimited(RateLimited),
+    /// The response doesn't match the types of this library
+    Decode(DecodeError),
     Other,
 }
 
@@ -35,11 +85,127 @@ pub struct UserFeedResponse {
     pub feed: UserFeed,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct TimelineFeedResponse {
+    #[serde(
+        rename = "user",
+        deserialize_with = "deserializer::nested_timeline_feed"
+    )]
+    pub feed: TimelineFeed,
+}
+
 #[derive(Deserialize)]
 pub struct UserInfosResponse {
     pub user: UserInfos,
 }
 
+#[derive(Debug, Deserialize)]
+pub struct MediaInfosResponse {
+    pub shortcode_media: Media,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct MediaCommentsResponse {
+    #[serde(
+        rename = "shortcode_media",
+        deserialize_with = "deserializer::nested_media_comments"
+    )]
+    pub comments: MediaComments,
+}
+
+#[derive(Debug, Deserialize)]
+#[serde(tag = "status", rename_all = "lowercase")]
+pub enum ActionResponse<T> {
+    Ok(T),
+    Fail { message: String },
+}
+
+impl<T> ActionResponse<T> {
+    /// # Errors
+    ///
+    /// Will return `Err` if Instagram refused the action.
+    pub fn into_result(self) -> Result<T, ClientError> {
+        match self {
+            Self::Ok(data) => Ok(data),
+            Self::Fail { message } => Err(ClientError::ActionFailed(message)),
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EmptyResponse {}
+
+#[derive(Debug, Deserialize)]
+pub struct ReelsMediaResponse {
+    pub reels: HashMap<UserId, Reel>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HighlightsTrayResponse {
+    pub tray: Vec<Highlight>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct HighlightsMediaResponse {
+    pub reels: HashMap<String, Highlight>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UploadPhotoResponse {
+    pub upload_id: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfigureMediaResponse {
+    pub media: ConfiguredMedia,
+}
+
+/// Media as described by the private API once a photo has been configured
+#[derive(Debug, Deserialize)]
+pub struct ConfiguredMedia {
+    pub pk: MediaId,
+    pub code: Shortcode,
+    pub taken_at: Timestamp,
+    pub original_width: i32,
+    pub original_height: i32,
+    pub image_versions2: ConfiguredMediaImages,
+    pub caption: Option<EditedMediaCaption>,
+    pub user: ConfiguredMediaUser,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfiguredMediaImages {
+    pub candidates: Vec<ConfiguredMediaCandidate>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfiguredMediaCandidate {
+    pub url: String,
+    pub width: i32,
+    pub height: i32,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ConfiguredMediaUser {
+    pub pk: UserId,
+    pub username: String,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EditMediaResponse {
+    pub media: EditedMedia,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EditedMedia {
+    pub caption: Option<EditedMediaCaption>,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EditedMediaCaption {
+    pub text: String,
+}
+
 #[derive(Debug, Deserialize)]
 #[serde(untagged)]
 pub enum LoginResponse {
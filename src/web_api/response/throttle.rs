@@ -0,0 +1,257 @@
+use serde::Deserialize;
+use std::time::Duration;
+
+use crate::web_api::error::{RateLimitKind, RateLimited};
+use crate::web_api::transport::{HeaderMap, Response, StatusCode};
+
+const MINUTE: u64 = 60;
+const HOUR: u64 = 60 * MINUTE;
+
+/// Suggested waits when Instagram doesn't send a `retry-after` header
+const TOO_MANY_REQUESTS_WAIT: u64 = 2 * MINUTE;
+const PLEASE_WAIT_WAIT: u64 = 10 * MINUTE;
+const FEEDBACK_REQUIRED_WAIT: u64 = 24 * HOUR;
+const LOGIN_REDIRECT_WAIT: u64 = HOUR;
+
+/// Start of the message asking to slow down, in lowercase
+const PLEASE_WAIT: &str = "please wait a few minutes";
+
+/// Fields of the error payloads sent instead of the data
+#[derive(Debug, Default, Deserialize)]
+struct ErrorPayload {
+    message: Option<String>,
+    feedback_required: Option<bool>,
+    feedback_title: Option<String>,
+    feedback_message: Option<String>,
+    spam: Option<bool>,
+}
+
+fn retry_after(headers: &HeaderMap) -> Option<Duration> {
+    headers
+        .get("retry-after")?
+        .to_str()
+        .ok()?
+        .trim()
+        .parse()
+        .ok()
+        .map(Duration::from_secs)
+}
+
+/// Whether Instagram sent its login page, or a redirection to it, instead of JSON
+///
+/// A page merely linking to the login page, like a 404, isn't one.
+fn is_login_page(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> bool {
+    if status.is_redirection() {
+        return headers
+            .get("location")
+            .and_then(|location| location.to_str().ok())
+            .is_some_and(|location| location.contains("/accounts/login"));
+    }
+
+    let body = String::from_utf8_lossy(body);
+
+    body.trim_start().starts_with('<') && body.contains("LoginAndSignupPage")
+}
+
+/// Sentence asking to slow down in a body which isn't JSON, like a plain text or an HTML page
+fn please_wait_sentence(body: &[u8]) -> Option<String> {
+    let body = String::from_utf8_lossy(body);
+    let start = body.to_ascii_lowercase().find(PLEASE_WAIT)?;
+    let sentence = &body[start..];
+    let end = sentence
+        .find(['.', '<', '\n'])
+        .map_or(sentence.len(), |end| {
+            if sentence[end..].starts_with('.') {
+                end + 1
+            } else {
+                end
+            }
+        });
+
+    Some(sentence[..end].trim().to_string())
+}
+
+/// Rate limit or soft block signaled by an unsuccessful response, if any
+///
+/// A successful response is only inspected once its body failed to decode, with
+/// [`detect_in_body`].
+pub fn detect(response: &Response) -> Option<RateLimited> {
+    if response.status.is_success() {
+        None
+    } else {
+        inspect(response.status, &response.headers, &response.body)
+    }
+}
+
+/// Rate limit or soft block signaled by the body of a successful response which isn't the
+/// expected data, like a login page or a "Please wait" payload, if any
+pub fn detect_in_body(body: &[u8]) -> Option<RateLimited> {
+    inspect(StatusCode::OK, &HeaderMap::new(), body)
+}
+
+fn inspect(status: StatusCode, headers: &HeaderMap, body: &[u8]) -> Option<RateLimited> {
+    let (payload, please_wait) = serde_json::from_slice::<ErrorPayload>(body).map_or_else(
+        |_| (ErrorPayload::default(), please_wait_sentence(body)),
+        |payload| (payload, None),
+    );
+    let message = payload.message.unwrap_or_default();
+
+    let (kind, message, wait) = if status == StatusCode::TOO_MANY_REQUESTS {
+        let message = if message.is_empty() {
+            status.to_string()
+        } else {
+            message
+        };
+
+        (
+            RateLimitKind::TooManyRequests,
+            message,
+            TOO_MANY_REQUESTS_WAIT,
+        )
+    } else if message.to_lowercase().contains(PLEASE_WAIT) {
+        (RateLimitKind::PleaseWait, message, PLEASE_WAIT_WAIT)
+    } else if let Some(message) = please_wait {
+        (RateLimitKind::PleaseWait, message, PLEASE_WAIT_WAIT)
+    } else if message == "feedback_required"
+        || payload.feedback_required == Some(true)
+        || payload.spam == Some(true)
+    {
+        let message = payload
+            .feedback_message
+            .or(payload.feedback_title)
+            .unwrap_or(message);
+
+        (
+            RateLimitKind::FeedbackRequired,
+            message,
+            FEEDBACK_REQUIRED_WAIT,
+        )
+    } else if is_login_page(status, headers, body) {
+        (
+            RateLimitKind::LoginRedirect,
+            "redirected to the login page".to_string(),
+            LOGIN_REDIRECT_WAIT,
+        )
+    } else {
+        return None;
+    };
+
+    Some(RateLimited {
+        kind,
+        message,
+        retry_after: retry_after(headers).unwrap_or_else(|| Duration::from_secs(wait)),
+    })
+}
+
+/// Body of the response, unless it's unsuccessful and signals a rate limit or a soft block
+pub fn check(response: Response) -> Result<Vec<u8>, RateLimited> {
+    detect(&response).map_or(Ok(response.body), Err)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::web_api::transport::HeaderValue;
+
+    fn response(status: StatusCode, body: &str) -> Response {
+        Response {
+            status,
+            headers: HeaderMap::new(),
+            body: body.as_bytes().to_vec(),
+        }
+    }
+
+    #[test]
+    fn test_rate_limits_are_detected() {
+        let mut too_many = response(StatusCode::TOO_MANY_REQUESTS, "");
+
+        too_many
+            .headers
+            .insert("retry-after", HeaderValue::from_static("30"));
+
+        let limited = detect(&too_many).unwrap();
+
+        assert_eq!(limited.kind(), RateLimitKind::TooManyRequests);
+        assert_eq!(limited.message(), "429 Too Many Requests");
+        assert_eq!(limited.retry_after(), Duration::from_secs(30));
+
+        let limited = detect(&response(
+            StatusCode::BAD_REQUEST,
+            r#"{"message": "Please wait a few minutes before you try again.", "status": "fail"}"#,
+        ))
+        .unwrap();
+
+        assert_eq!(limited.kind(), RateLimitKind::PleaseWait);
+        assert_eq!(limited.retry_after(), Duration::from_secs(PLEASE_WAIT_WAIT));
+
+        for body in &[
+            "Please wait a few minutes before you try again.",
+            "<!DOCTYPE html><html><body><p>Please wait a few minutes before you try again.</p></body></html>",
+        ] {
+            let limited = detect(&response(StatusCode::BAD_REQUEST, body)).unwrap();
+
+            assert_eq!(limited.kind(), RateLimitKind::PleaseWait);
+            assert_eq!(
+                limited.message(),
+                "Please wait a few minutes before you try again."
+            );
+        }
+
+        let limited = detect(&response(
+            StatusCode::BAD_REQUEST,
+            r#"{"message": "feedback_required", "spam": true, "feedback_title": "Action Blocked", "feedback_message": "This action was blocked. Please try again later.", "status": "fail"}"#,
+        ))
+        .unwrap();
+
+        assert_eq!(limited.kind(), RateLimitKind::FeedbackRequired);
+        assert_eq!(
+            limited.message(),
+            "This action was blocked. Please try again later."
+        );
+
+        let login_page = r#"<!DOCTYPE html><html><script>window._sharedData = {"entry_data":{"LoginAndSignupPage":[{}]}};</script></html>"#;
+
+        assert!(detect(&response(StatusCode::OK, login_page)).is_none());
+        assert_eq!(
+            detect_in_body(login_page.as_bytes()).map(|limited| limited.kind()),
+            Some(RateLimitKind::LoginRedirect)
+        );
+
+        let please_wait =
+            r#"{"message": "Please wait a few minutes before you try again.", "status": "fail"}"#;
+
+        assert!(detect(&response(StatusCode::OK, please_wait)).is_none());
+        assert_eq!(
+            detect_in_body(please_wait.as_bytes()).map(|limited| limited.kind()),
+            Some(RateLimitKind::PleaseWait)
+        );
+
+        let mut redirect = response(StatusCode::FOUND, "");
+
+        redirect.headers.insert(
+            "location",
+            HeaderValue::from_static("https://www.instagram.com/accounts/login/?next=/freyskeyd/"),
+        );
+
+        assert_eq!(
+            detect(&redirect).map(|limited| limited.kind()),
+            Some(RateLimitKind::LoginRedirect)
+        );
+    }
+
+    #[test]
+    fn test_regular_responses_are_kept() {
+        assert!(detect(&response(StatusCode::OK, r#"{"status": "ok"}"#)).is_none());
+        assert!(detect(&response(
+            StatusCode::BAD_REQUEST,
+            r#"{"message": "checkpoint_required", "status": "fail"}"#
+        ))
+        .is_none());
+        assert!(detect(&response(StatusCode::NOT_FOUND, "")).is_none());
+        assert!(detect(&response(
+            StatusCode::NOT_FOUND,
+            r#"<!DOCTYPE html><html><body>Sorry, this page isn't available. <a href="/accounts/login/">Log In</a></body></html>"#
+        ))
+        .is_none());
+    }
+}
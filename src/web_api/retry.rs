@@ -0,0 +1,201 @@
+use std::collections::hash_map::RandomState;
+use std::convert::TryFrom;
+use std::hash::{BuildHasher, Hasher};
+use std::time::{Duration, Instant};
+
+use crate::web_api::rate_limit::{Endpoint, RateLimiter};
+use crate::web_api::transport::{Request, Response, SharedTransport, StatusCode, TransportError};
+
+/// Retries of the requests failing on a connection error, a 5xx or a 429
+///
+/// The delay before each retry doubles from [`RetryPolicy::initial_backoff`] up to
+/// [`RetryPolicy::max_backoff`], a random part of it is removed by the jitter so concurrent
+/// clients don't retry all at once. Only the idempotent requests are retried unless
+/// [`RetryPolicy::retry_mutations`] is set, a like or a comment could be applied twice.
+///
+/// # Examples
+///
+/// ```rust
+/// use instagram::web_api::retry::RetryPolicy;
+/// use instagram::web_api::ClientBuilder;
+/// use std::time::Duration;
+///
+/// let client = ClientBuilder::new()
+///     .set_retry_policy(
+///         RetryPolicy::new()
+///             .max_retries(5)
+///             .max_elapsed_time(Duration::from_secs(60)),
+///     )
+///     .build();
+/// ```
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct RetryPolicy {
+    max_retries: u32,
+    initial_backoff: Duration,
+    max_backoff: Duration,
+    max_elapsed_time: Duration,
+    jitter: bool,
+    retry_mutations: bool,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(500),
+            max_backoff: Duration::from_secs(30),
+            max_elapsed_time: Duration::from_secs(90),
+            jitter: true,
+            retry_mutations: false,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// Three retries, from half a second up to 30 seconds apart and within a minute and a half
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Policy of the clients unless another one is set, a failed request is never retried
+    #[must_use]
+    pub fn none() -> Self {
+        Self::default().max_retries(0)
+    }
+
+    #[must_use]
+    pub const fn max_retries(mut self, max_retries: u32) -> Self {
+        self.max_retries = max_retries;
+
+        self
+    }
+
+    /// Delay before the first retry
+    #[must_use]
+    pub const fn initial_backoff(mut self, backoff: Duration) -> Self {
+        self.initial_backoff = backoff;
+
+        self
+    }
+
+    #[must_use]
+    pub const fn max_backoff(mut self, backoff: Duration) -> Self {
+        self.max_backoff = backoff;
+
+        self
+    }
+
+    /// Time after which a request isn't retried anymore, counted from its first attempt
+    #[must_use]
+    pub const fn max_elapsed_time(mut self, elapsed: Duration) -> Self {
+        self.max_elapsed_time = elapsed;
+
+        self
+    }
+
+    #[must_use]
+    pub const fn jitter(mut self, jitter: bool) -> Self {
+        self.jitter = jitter;
+
+        self
+    }
+
+    /// Retry the requests which aren't idempotent too, like posting or liking a media
+    #[must_use]
+    pub const fn retry_mutations(mut self, retry_mutations: bool) -> Self {
+        self.retry_mutations = retry_mutations;
+
+        self
+    }
+
+    /// Delay before the retry following the failed `attempt`, starting at 0
+    #[must_use]
+    pub fn backoff(&self, attempt: u32) -> Duration {
+        let backoff = self
+            .initial_backoff
+            .checked_mul(2_u32.saturating_pow(attempt))
+            .map_or(self.max_backoff, |backoff| backoff.min(self.max_backoff));
+
+        if self.jitter {
+            // Between half and the whole of the backoff
+            let random = u32::try_from(RandomState::new().build_hasher().finish() % 1000)
+                .unwrap_or_default();
+
+            backoff / 2 + backoff.mul_f64(f64::from(random) / 2000.0)
+        } else {
+            backoff
+        }
+    }
+
+    fn should_retry(result: &Result<Response, TransportError>) -> bool {
+        match result {
+            Ok(response) => {
+                response.status.is_server_error()
+                    || response.status == StatusCode::TOO_MANY_REQUESTS
+            }
+            Err(TransportError::Connection(_)) => true,
+            Err(_) => false,
+        }
+    }
+
+    /// Delay asked by a `retry-after` header, in seconds
+    fn retry_after(result: &Result<Response, TransportError>) -> Option<Duration> {
+        result
+            .as_ref()
+            .ok()?
+            .headers
+            .get("retry-after")?
+            .to_str()
+            .ok()?
+            .parse()
+            .ok()
+            .map(Duration::from_secs)
+    }
+
+    /// Send the request, retrying it as long as the policy allows
+    ///
+    /// Each attempt waits for a token of the endpoint's budget, a retry counts as a request.
+    pub(crate) async fn send(
+        &self,
+        transport: &SharedTransport,
+        rate_limiter: &RateLimiter,
+        endpoint: Option<Endpoint>,
+        request: Request,
+    ) -> Result<Response, TransportError> {
+        if self.max_retries == 0 || !(self.retry_mutations || request.method.is_idempotent()) {
+            if let Some(endpoint) = endpoint {
+                rate_limiter.acquire(endpoint).await;
+            }
+
+            return transport.send(request).await;
+        }
+
+        let start = Instant::now();
+        let mut attempt = 0;
+
+        loop {
+            if let Some(ref endpoint) = endpoint {
+                rate_limiter.acquire(endpoint.clone()).await;
+            }
+
+            let result = transport.send(request.clone()).await;
+
+            if attempt >= self.max_retries || !Self::should_retry(&result) {
+                return result;
+            }
+
+            let backoff = Self::retry_after(&result).map_or_else(
+                || self.backoff(attempt),
+                |delay| delay.max(self.backoff(attempt)),
+            );
+
+            if start.elapsed() + backoff > self.max_elapsed_time {
+                return result;
+            }
+
+            tokio::time::delay_for(backoff).await;
+            attempt += 1;
+        }
+    }
+}
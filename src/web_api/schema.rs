@@ -0,0 +1,427 @@
+use serde::de::{
+    self, value::StringDeserializer, DeserializeOwned, DeserializeSeed, Deserializer, MapAccess,
+    SeqAccess, Visitor,
+};
+use serde_json::{Map, Value};
+use std::fmt;
+use std::sync::Arc;
+
+use crate::web_api::error::DecodeError;
+
+/// Names standing for the same field of a type, one of them is enough
+const ALIASED_FIELDS: &[&[&str]] = &[&["edge_media_to_comment", "edge_media_preview_comment"]];
+
+/// Difference between a response sent by Instagram and the types of this library
+///
+/// Reported by a [`Client`](crate::web_api::Client) in strict mode, the path uses the response
+/// keys, as in `graphql.user.edge_owner_to_timeline_media.edges[0].node.accessibility_caption`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SchemaDrift {
+    /// The response holds a field the library doesn't decode
+    UnknownField(String),
+    /// The response lacks a field the library decodes, the decoding fails unless the field is
+    /// optional
+    MissingField(String),
+}
+
+impl SchemaDrift {
+    #[must_use]
+    pub fn path(&self) -> &str {
+        match self {
+            Self::UnknownField(path) | Self::MissingField(path) => path,
+        }
+    }
+}
+
+impl fmt::Display for SchemaDrift {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::UnknownField(path) => write!(f, "unknown field `{path}`"),
+            Self::MissingField(path) => write!(f, "missing field `{path}`"),
+        }
+    }
+}
+
+/// Callback receiving the schema drifts of a strict client
+#[derive(Clone)]
+pub(crate) struct DriftHandler(Arc<dyn Fn(&SchemaDrift) + Send + Sync>);
+
+impl DriftHandler {
+    pub(crate) fn new<F>(on_drift: F) -> Self
+    where
+        F: Fn(&SchemaDrift) + Send + Sync + 'static,
+    {
+        Self(Arc::new(on_drift))
+    }
+
+    pub(crate) fn report(&self, drift: &SchemaDrift) {
+        (self.0)(drift);
+    }
+}
+
+impl fmt::Debug for DriftHandler {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("DriftHandler")
+    }
+}
+
+impl PartialEq for DriftHandler {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
+
+fn join(parent: &str, key: &str) -> String {
+    if parent.is_empty() {
+        key.to_string()
+    } else if key.is_empty() {
+        parent.to_string()
+    } else {
+        format!("{parent}.{key}")
+    }
+}
+
+fn ignored_path(path: &serde_ignored::Path<'_>) -> String {
+    match path {
+        serde_ignored::Path::Root => String::new(),
+        serde_ignored::Path::Seq { parent, index } => format!("{}[{index}]", ignored_path(parent)),
+        serde_ignored::Path::Map { parent, key } => join(&ignored_path(parent), key),
+        serde_ignored::Path::Some { parent }
+        | serde_ignored::Path::NewtypeStruct { parent }
+        | serde_ignored::Path::NewtypeVariant { parent } => ignored_path(parent),
+    }
+}
+
+fn error_path(path: &serde_path_to_error::Path) -> String {
+    path.iter()
+        .fold(String::new(), |path, segment| match segment {
+            serde_path_to_error::Segment::Seq { index } => format!("{path}[{index}]"),
+            serde_path_to_error::Segment::Map { key } => join(&path, key),
+            serde_path_to_error::Segment::Enum { variant } => join(&path, variant),
+            serde_path_to_error::Segment::Unknown => join(&path, "?"),
+        })
+}
+
+/// Report the `fields` absent from an object located at `path`
+pub(crate) fn report_missing_fields(
+    object: &Map<String, Value>,
+    fields: &[&str],
+    path: &str,
+    handler: &DriftHandler,
+) {
+    let is_present = |field: &str| {
+        ALIASED_FIELDS
+            .iter()
+            .find(|aliases| aliases.contains(&field))
+            .map_or_else(
+                || object.contains_key(field),
+                |aliases| aliases.iter().any(|alias| object.contains_key(*alias)),
+            )
+    };
+
+    for field in fields.iter().filter(|field| !is_present(field)) {
+        handler.report(&SchemaDrift::MissingField(join(path, field)));
+    }
+}
+
+/// Deserializer of a JSON value comparing each object with the fields its type expects
+struct Expecting<'a> {
+    value: Value,
+    path: String,
+    handler: &'a DriftHandler,
+}
+
+impl<'de> Deserializer<'de> for Expecting<'_> {
+    type Error = serde_json::Error;
+
+    fn deserialize_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Object(object) => visitor.visit_map(ExpectingMap {
+                entries: object.into_iter(),
+                value: None,
+                path: self.path,
+                handler: self.handler,
+            }),
+            Value::Array(items) => visitor.visit_seq(ExpectingSeq {
+                items: items.into_iter().enumerate(),
+                path: self.path,
+                handler: self.handler,
+            }),
+            value => value.deserialize_any(visitor),
+        }
+    }
+
+    fn deserialize_option<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        match self.value {
+            Value::Null => visitor.visit_none(),
+            _ => visitor.visit_some(self),
+        }
+    }
+
+    fn deserialize_newtype_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        visitor.visit_newtype_struct(self)
+    }
+
+    fn deserialize_struct<V: Visitor<'de>>(
+        self,
+        _name: &'static str,
+        fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        if let Value::Object(ref object) = self.value {
+            report_missing_fields(object, fields, &self.path, self.handler);
+        }
+
+        self.deserialize_any(visitor)
+    }
+
+    fn deserialize_enum<V: Visitor<'de>>(
+        self,
+        name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error> {
+        self.value.deserialize_enum(name, variants, visitor)
+    }
+
+    fn deserialize_ignored_any<V: Visitor<'de>>(self, visitor: V) -> Result<V::Value, Self::Error> {
+        self.value.deserialize_ignored_any(visitor)
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 i128 u8 u16 u32 u64 u128 f32 f64 char str string bytes byte_buf unit
+        unit_struct seq tuple tuple_struct map identifier
+    }
+}
+
+struct ExpectingMap<'a> {
+    entries: serde_json::map::IntoIter,
+    value: Option<Expecting<'a>>,
+    path: String,
+    handler: &'a DriftHandler,
+}
+
+impl<'de> MapAccess<'de> for ExpectingMap<'_> {
+    type Error = serde_json::Error;
+
+    fn next_key_seed<K: DeserializeSeed<'de>>(
+        &mut self,
+        seed: K,
+    ) -> Result<Option<K::Value>, Self::Error> {
+        let Some((key, value)) = self.entries.next() else {
+            return Ok(None);
+        };
+
+        self.value = Some(Expecting {
+            value,
+            path: join(&self.path, &key),
+            handler: self.handler,
+        });
+
+        seed.deserialize(StringDeserializer::new(key)).map(Some)
+    }
+
+    fn next_value_seed<V: DeserializeSeed<'de>>(
+        &mut self,
+        seed: V,
+    ) -> Result<V::Value, Self::Error> {
+        let value = self
+            .value
+            .take()
+            .ok_or_else(|| de::Error::custom("value is missing"))?;
+
+        seed.deserialize(value)
+    }
+}
+
+struct ExpectingSeq<'a> {
+    items: std::iter::Enumerate<std::vec::IntoIter<Value>>,
+    path: String,
+    handler: &'a DriftHandler,
+}
+
+impl<'de> SeqAccess<'de> for ExpectingSeq<'_> {
+    type Error = serde_json::Error;
+
+    fn next_element_seed<T: DeserializeSeed<'de>>(
+        &mut self,
+        seed: T,
+    ) -> Result<Option<T::Value>, Self::Error> {
+        self.items
+            .next()
+            .map(|(index, value)| {
+                seed.deserialize(Expecting {
+                    value,
+                    path: format!("{}[{index}]", self.path),
+                    handler: self.handler,
+                })
+            })
+            .transpose()
+    }
+}
+
+fn decode_error(path: &str, error: serde_path_to_error::Error<serde_json::Error>) -> DecodeError {
+    DecodeError {
+        path: join(path, &error_path(error.path())),
+        message: error.into_inner().to_string(),
+    }
+}
+
+/// Decode a value located at `path` in a response, reporting its unknown and missing fields
+fn decode_reported<T: DeserializeOwned>(
+    value: Value,
+    handler: &DriftHandler,
+    path: &str,
+    known_root_fields: &[&str],
+) -> Result<T, DecodeError> {
+    let mut on_ignored = |ignored: serde_ignored::Path<'_>| {
+        let ignored = ignored_path(&ignored);
+
+        if !known_root_fields.contains(&ignored.as_str()) {
+            handler.report(&SchemaDrift::UnknownField(join(path, &ignored)));
+        }
+    };
+    let expecting = Expecting {
+        value,
+        path: path.to_string(),
+        handler,
+    };
+
+    serde_path_to_error::deserialize(serde_ignored::Deserializer::new(expecting, &mut on_ignored))
+        .map_err(|error| decode_error(path, error))
+}
+
+/// Decode a response body, reporting its drifts to the handler when there is one
+///
+/// Fields at the root of the response named in `known_root_fields` are not reported.
+pub(crate) fn decode<T: DeserializeOwned>(
+    body: &[u8],
+    handler: Option<&DriftHandler>,
+    known_root_fields: &[&str],
+) -> Result<T, DecodeError> {
+    if let Some(handler) = handler {
+        return decode_reported(
+            serde_json::from_slice(body)?,
+            handler,
+            "",
+            known_root_fields,
+        );
+    }
+
+    let mut deserializer = serde_json::Deserializer::from_slice(body);
+    let decoded = serde_path_to_error::deserialize(&mut deserializer)
+        .map_err(|error| decode_error("", error))?;
+
+    deserializer.end()?;
+
+    Ok(decoded)
+}
+
+/// Decode a value located at `path` in a response, reporting its drifts to the handler when
+/// there is one
+pub(crate) fn decode_value<T: DeserializeOwned>(
+    value: Value,
+    handler: Option<&DriftHandler>,
+    path: &str,
+) -> Result<T, DecodeError> {
+    match handler {
+        Some(handler) => decode_reported(value, handler, path, &[]),
+        None => serde_path_to_error::deserialize(value).map_err(|error| decode_error(path, error)),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{decode, decode_value, DriftHandler, SchemaDrift};
+    use serde::Deserialize;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Debug, Deserialize)]
+    struct Feed {
+        #[allow(dead_code)]
+        edges: Vec<Edge>,
+    }
+
+    #[derive(Debug, Deserialize)]
+    struct Edge {
+        #[allow(dead_code)]
+        id: String,
+        #[allow(dead_code)]
+        caption: Option<String>,
+    }
+
+    fn collector() -> (DriftHandler, Arc<Mutex<Vec<SchemaDrift>>>) {
+        let drifts = Arc::new(Mutex::new(Vec::new()));
+        let collected = Arc::clone(&drifts);
+
+        let handler = DriftHandler::new(move |drift| collected.lock().unwrap().push(drift.clone()));
+
+        (handler, drifts)
+    }
+
+    #[test]
+    fn test_unknown_fields_are_reported() {
+        let (handler, drifts) = collector();
+        let body = br#"{"status": "ok", "extensions": {}, "edges": [{"id": "1", "caption": null}, {"id": "2", "caption": "", "is_ad": true}]}"#;
+
+        let feed: Result<Feed, _> = decode(body, Some(&handler), &["status"]);
+
+        assert!(feed.is_ok());
+        assert_eq!(
+            *drifts.lock().unwrap(),
+            vec![
+                SchemaDrift::UnknownField("extensions".into()),
+                SchemaDrift::UnknownField("edges[1].is_ad".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_fields_are_reported() {
+        let (handler, drifts) = collector();
+        let body = br#"{"edges": [{"id": "1"}, {"caption": "no id"}]}"#;
+
+        let feed: Result<Feed, _> = decode(body, Some(&handler), &[]);
+
+        assert!(feed.is_err());
+        assert_eq!(
+            *drifts.lock().unwrap(),
+            vec![
+                SchemaDrift::MissingField("edges[0].caption".into()),
+                SchemaDrift::MissingField("edges[1].id".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_missing_optional_fields_are_reported() {
+        let (handler, drifts) = collector();
+        let value =
+            serde_json::json!({"edges": [{"id": "1"}, {"id": "2"}, {"id": "3", "caption": null}]});
+
+        let feed: Result<Feed, _> = decode_value(value, Some(&handler), "data.feed");
+
+        assert!(feed.is_ok());
+        assert_eq!(
+            *drifts.lock().unwrap(),
+            vec![
+                SchemaDrift::MissingField("data.feed.edges[0].caption".into()),
+                SchemaDrift::MissingField("data.feed.edges[1].caption".into()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_decode_errors_hold_their_path() {
+        let body = br#"{"edges": [{"id": "1"}, {"id": 2}]}"#;
+
+        let error = decode::<Feed>(body, None, &[]).unwrap_err();
+
+        assert_eq!(error.path(), "edges[1].id");
+        assert!(error.message().starts_with("invalid type: integer `2`"));
+    }
+}
@@ -0,0 +1,956 @@
+//! In-memory Instagram, to test applications without a network or fixtures
+//!
+//! [`FakeInstagram`] keeps users, posts, comments, follows and stories, and implements every
+//! behaviour of [`web_api::behaviour`](crate::web_api::behaviour) on top of them. The answers
+//! are decoded from the same payloads Instagram sends, so they go through the types of this
+//! library like the ones of a real client.
+//!
+//! # Examples
+//!
+//! ```rust
+//! use instagram::web_api::behaviour::{EditMediaCaption, FetchUserFeed};
+//! use instagram::web_api::testing::FakeInstagram;
+//! # async fn doc() {
+//! let instagram = FakeInstagram::new();
+//!
+//! let alice = instagram.add_user("alice");
+//! let post = instagram.add_post(alice, "First post #hello");
+//!
+//! instagram.log_in(alice);
+//! instagram.edit_media_caption(post, "Edited").await.unwrap();
+//!
+//! let feed = instagram.fetch_user_feed(alice, None).await.unwrap();
+//!
+//! assert_eq!(feed.medias[0].caption(), Some("Edited"));
+//! # }
+//! ```
+use async_trait::async_trait;
+use serde_json::{json, Value};
+use std::collections::{BTreeMap, BTreeSet};
+use std::convert::TryFrom;
+use std::sync::{Arc, Mutex, MutexGuard, PoisonError};
+
+use crate::web_api::{
+    behaviour::{
+        ArchiveMedia, DeleteMedia, EditMediaCaption, FetchHighlightReels, FetchMediaComments,
+        FetchMediaInfos, FetchReelsFeed, FetchReelsTray, FetchTimelineFeed, FetchUserFeed,
+        FetchUserInfos, MarkStoriesAsSeen, PostPhoto,
+    },
+    client::upload,
+    domain::{
+        CommentId, Highlight, Media, MediaArchiving, MediaCaptionEdition, MediaComments,
+        MediaDeletion, MediaId, Reel, ReelItem, ReelsTray, StoriesSeen, TimelineFeed, Timestamp,
+        UserFeed, UserId, UserInfos,
+    },
+    error::ClientError,
+    options::{
+        FetchMediaCommentsOptions, FetchTimelineFeedOptions, FetchUserFeedOptions,
+        PaginationOptions, Photo, PostPhotoOptions, UserTag,
+    },
+    response::UserInfosError,
+};
+
+/// Host of the image urls of the fake
+pub const FAKE_CDN: &str = "https://cdn.instagram.test";
+
+/// Lifetime of a story, in seconds
+const STORY_LIFETIME: i64 = 24 * 60 * 60;
+
+const FIRST_USER_ID: u64 = 1_000;
+const FIRST_MEDIA_ID: u64 = 3_000_000_000_000_000_000;
+const FIRST_COMMENT_ID: u64 = 18_000_000_000_000_000;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeUser {
+    pub id: UserId,
+    pub username: String,
+    pub full_name: String,
+    pub biography: String,
+    pub is_private: bool,
+    pub is_verified: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeLocation {
+    pub id: String,
+    pub name: String,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct FakePost {
+    pub id: MediaId,
+    pub owner: UserId,
+    pub caption: Option<String>,
+    pub taken_at: Timestamp,
+    pub width: u16,
+    pub height: u16,
+    pub like_count: i32,
+    /// Only visible to its owner
+    pub archived: bool,
+    pub location: Option<FakeLocation>,
+    pub user_tags: Vec<UserTag>,
+    pub comments: Vec<FakeComment>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeComment {
+    pub id: CommentId,
+    pub author: UserId,
+    pub text: String,
+    pub created_at: Timestamp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeStory {
+    pub id: MediaId,
+    pub owner: UserId,
+    pub taken_at: Timestamp,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FakeHighlight {
+    pub id: String,
+    pub owner: UserId,
+    pub title: String,
+    pub stories: Vec<MediaId>,
+}
+
+#[derive(Debug, Default)]
+struct State {
+    next_id: u64,
+    viewer: Option<UserId>,
+    users: BTreeMap<UserId, FakeUser>,
+    /// `(follower, followed)`
+    follows: BTreeSet<(UserId, UserId)>,
+    posts: BTreeMap<MediaId, FakePost>,
+    stories: BTreeMap<MediaId, FakeStory>,
+    highlights: Vec<FakeHighlight>,
+    /// Latest story seen by a viewer, by `(viewer, owner)`
+    seen: BTreeMap<(UserId, UserId), Timestamp>,
+}
+
+/// In-memory Instagram implementing all the behaviours
+///
+/// The clones share the same data. Actions requiring a login are done as the user given to
+/// [`log_in`](Self::log_in), they fail with `login_required` before.
+#[derive(Debug, Clone, Default)]
+pub struct FakeInstagram {
+    state: Arc<Mutex<State>>,
+}
+
+impl FakeInstagram {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn state(&self) -> MutexGuard<'_, State> {
+        self.state.lock().unwrap_or_else(PoisonError::into_inner)
+    }
+
+    /// Add a public user, its full name is its username
+    #[must_use]
+    pub fn add_user(&self, username: &str) -> UserId {
+        let mut state = self.state();
+        let id = UserId::new(FIRST_USER_ID + state.next_id());
+
+        state.users.insert(
+            id,
+            FakeUser {
+                id,
+                username: username.to_string(),
+                full_name: username.to_string(),
+                biography: String::new(),
+                is_private: false,
+                is_verified: false,
+            },
+        );
+
+        id
+    }
+
+    /// Change the profile of a user, to make it private or verified
+    ///
+    /// # Panics
+    ///
+    /// Will panic if the user doesn't exist.
+    pub fn update_user<F: FnOnce(&mut FakeUser)>(&self, user_id: UserId, update: F) {
+        update(
+            self.state()
+                .users
+                .get_mut(&user_id)
+                .expect("Unknown fake user"),
+        );
+    }
+
+    pub fn follow(&self, follower: UserId, target: UserId) {
+        self.state().follows.insert((follower, target));
+    }
+
+    pub fn unfollow(&self, follower: UserId, target: UserId) {
+        self.state().follows.remove(&(follower, target));
+    }
+
+    /// Add a square photo posted now
+    #[must_use]
+    pub fn add_post(&self, owner: UserId, caption: &str) -> MediaId {
+        self.state().add_post(FakePost {
+            id: MediaId::new(0),
+            owner,
+            caption: Some(caption.to_string()).filter(|caption| !caption.is_empty()),
+            taken_at: Timestamp::now(),
+            width: 1080,
+            height: 1080,
+            like_count: 0,
+            archived: false,
+            location: None,
+            user_tags: Vec::new(),
+            comments: Vec::new(),
+        })
+    }
+
+    /// # Panics
+    ///
+    /// Will panic if the media doesn't exist.
+    #[must_use]
+    pub fn add_comment(&self, media_id: MediaId, author: UserId, text: &str) -> CommentId {
+        let mut state = self.state();
+        let id = CommentId::new(FIRST_COMMENT_ID + state.next_id());
+
+        state
+            .posts
+            .get_mut(&media_id)
+            .expect("Unknown fake media")
+            .comments
+            .push(FakeComment {
+                id,
+                author,
+                text: text.to_string(),
+                created_at: Timestamp::now(),
+            });
+
+        id
+    }
+
+    /// # Panics
+    ///
+    /// Will panic if the media doesn't exist.
+    pub fn like(&self, media_id: MediaId) {
+        self.state()
+            .posts
+            .get_mut(&media_id)
+            .expect("Unknown fake media")
+            .like_count += 1;
+    }
+
+    /// Add a story posted now, it expires after a day
+    #[must_use]
+    pub fn add_story(&self, owner: UserId) -> MediaId {
+        let mut state = self.state();
+        let id = MediaId::new(FIRST_MEDIA_ID + state.next_id());
+
+        state.stories.insert(
+            id,
+            FakeStory {
+                id,
+                owner,
+                taken_at: Timestamp::now(),
+            },
+        );
+
+        id
+    }
+
+    /// Pin stories to the profile of their owner, they're kept once expired
+    #[must_use]
+    pub fn add_highlight(&self, owner: UserId, title: &str, stories: &[MediaId]) -> String {
+        let mut state = self.state();
+        let id = format!("highlight:{}", FIRST_MEDIA_ID + state.next_id());
+
+        state.highlights.push(FakeHighlight {
+            id: id.clone(),
+            owner,
+            title: title.to_string(),
+            stories: stories.to_vec(),
+        });
+
+        id
+    }
+
+    /// Act as this user for the actions requiring a login
+    pub fn log_in(&self, user_id: UserId) {
+        self.state().viewer = Some(user_id);
+    }
+
+    pub fn log_out(&self) {
+        self.state().viewer = None;
+    }
+
+    #[must_use]
+    pub fn user(&self, user_id: UserId) -> Option<FakeUser> {
+        self.state().users.get(&user_id).cloned()
+    }
+
+    #[must_use]
+    pub fn post(&self, media_id: MediaId) -> Option<FakePost> {
+        self.state().posts.get(&media_id).cloned()
+    }
+
+    #[must_use]
+    pub fn follows(&self, follower: UserId, target: UserId) -> bool {
+        self.state().follows.contains(&(follower, target))
+    }
+}
+
+fn login_required() -> ClientError {
+    ClientError::ActionFailed("login_required".to_string())
+}
+
+/// Page of a list following the offset given as cursor
+fn paginate<T>(items: Vec<T>, count: i16, after: Option<&str>) -> (Vec<T>, Option<String>) {
+    let start = after.and_then(|after| after.parse().ok()).unwrap_or(0);
+    let end = start + usize::try_from(count).unwrap_or(0);
+    let next_cursor = Some(end.to_string()).filter(|_| end < items.len());
+
+    (
+        items.into_iter().skip(start).take(end - start).collect(),
+        next_cursor,
+    )
+}
+
+fn page_info(next_cursor: Option<&str>) -> Value {
+    json!({
+        "has_next_page": next_cursor.is_some(),
+        "end_cursor": next_cursor,
+    })
+}
+
+impl State {
+    const fn next_id(&mut self) -> u64 {
+        self.next_id += 1;
+
+        self.next_id
+    }
+
+    fn add_post(&mut self, mut post: FakePost) -> MediaId {
+        post.id = MediaId::new(FIRST_MEDIA_ID + self.next_id());
+
+        let id = post.id;
+
+        self.posts.insert(id, post);
+
+        id
+    }
+
+    fn viewer(&self) -> Result<UserId, ClientError> {
+        self.viewer.ok_or_else(login_required)
+    }
+
+    fn follows(&self, follower: UserId, target: UserId) -> bool {
+        self.follows.contains(&(follower, target))
+    }
+
+    /// Users followed by a user
+    fn followed_by(&self, follower: UserId) -> impl Iterator<Item = UserId> + '_ {
+        self.follows
+            .iter()
+            .filter(move |(user_id, _)| *user_id == follower)
+            .map(|(_, followed)| *followed)
+    }
+
+    /// Whether the viewer can see the posts of a user
+    fn can_see(&self, owner: &FakeUser) -> bool {
+        !owner.is_private
+            || self
+                .viewer
+                .is_some_and(|viewer| viewer == owner.id || self.follows(viewer, owner.id))
+    }
+
+    /// Posts of the users, newest first, the archived ones are only listed for their owner
+    fn posts_of(&self, owners: &[UserId]) -> Vec<&FakePost> {
+        let mut posts: Vec<&FakePost> = self
+            .posts
+            .values()
+            .filter(|post| owners.contains(&post.owner))
+            .filter(|post| !post.archived || self.viewer == Some(post.owner))
+            .collect();
+
+        posts.sort_by_key(|post| std::cmp::Reverse((post.taken_at, post.id)));
+
+        posts
+    }
+
+    /// Post the viewer may act on
+    fn own_post(&mut self, media_id: MediaId) -> Result<&mut FakePost, ClientError> {
+        let viewer = self.viewer()?;
+
+        match self.posts.get_mut(&media_id) {
+            Some(post) if post.owner == viewer => Ok(post),
+            Some(_) => Err(ClientError::ActionFailed(
+                "You cannot edit this media".to_string(),
+            )),
+            None => Err(ClientError::ActionFailed("Media not found".to_string())),
+        }
+    }
+
+    fn live_stories(&self, owner: UserId) -> Vec<&FakeStory> {
+        let now = Timestamp::now().as_secs();
+
+        self.stories
+            .values()
+            .filter(|story| story.owner == owner)
+            .filter(|story| story.taken_at.as_secs() + STORY_LIFETIME > now)
+            .collect()
+    }
+
+    fn user_json(&self, user: &FakeUser) -> Value {
+        let followers = self.follows.iter().filter(|(_, b)| *b == user.id).count();
+        let following = self.follows.iter().filter(|(a, _)| *a == user.id).count();
+        let posts = self.posts_of(&[user.id]);
+        let viewer = self.viewer;
+
+        let edges: Vec<Value> = if self.can_see(user) {
+            posts
+                .iter()
+                .take(12)
+                .map(|post| json!({ "node": self.media_json(post) }))
+                .collect()
+        } else {
+            Vec::new()
+        };
+
+        json!({
+            "biography": user.biography,
+            "blocked_by_viewer": false,
+            "restricted_by_viewer": false,
+            "country_block": false,
+            "external_url": null,
+            "external_url_linkshimmed": null,
+            "followed_by_viewer": viewer.is_some_and(|viewer| self.follows(viewer, user.id)),
+            "follows_viewer": viewer.is_some_and(|viewer| self.follows(user.id, viewer)),
+            "full_name": user.full_name,
+            "has_ar_effects": false,
+            "has_channel": false,
+            "has_blocked_viewer": false,
+            "highlight_reel_count": self.highlights.iter().filter(|h| h.owner == user.id).count(),
+            "has_requested_viewer": false,
+            "id": user.id.to_string(),
+            "is_business_account": false,
+            "is_joined_recently": false,
+            "business_category_name": "",
+            "category_id": "",
+            "overall_category_name": null,
+            "is_private": user.is_private,
+            "is_verified": user.is_verified,
+            "profile_pic_url": format!("{FAKE_CDN}/profiles/{}.jpg", user.id),
+            "profile_pic_url_hd": format!("{FAKE_CDN}/profiles/{}_hd.jpg", user.id),
+            "requested_by_viewer": false,
+            "username": user.username,
+            "connected_fb_page": null,
+            "edge_followed_by": { "count": followers },
+            "edge_follow": { "count": following },
+            "edge_owner_to_timeline_media": {
+                "count": posts.len(),
+                "page_info": page_info(None),
+                "edges": edges,
+            },
+        })
+    }
+
+    fn username(&self, user_id: UserId) -> String {
+        self.users
+            .get(&user_id)
+            .map(|user| user.username.clone())
+            .unwrap_or_default()
+    }
+
+    fn comment_json(&self, comment: &FakeComment) -> Value {
+        json!({
+            "id": comment.id.to_string(),
+            "created_at": comment.created_at,
+            "did_report_as_spam": false,
+            "text": comment.text,
+            "viewer_has_liked": false,
+            "owner": {
+                "id": comment.author.to_string(),
+                "is_verified": self.users.get(&comment.author).is_some_and(|user| user.is_verified),
+                "profile_pic_url": format!("{FAKE_CDN}/profiles/{}.jpg", comment.author),
+                "username": self.username(comment.author),
+            },
+        })
+    }
+
+    fn media_json(&self, post: &FakePost) -> Value {
+        let comments: Vec<Value> = post
+            .comments
+            .iter()
+            .map(|comment| json!({ "node": self.comment_json(comment) }))
+            .collect();
+
+        let captions: Vec<Value> = post
+            .caption
+            .iter()
+            .map(|caption| json!({ "node": { "text": caption } }))
+            .collect();
+
+        let user_tags: Vec<Value> = post
+            .user_tags
+            .iter()
+            .map(|tag| {
+                json!({ "node": {
+                    "user": {
+                        "id": tag.user_id.to_string(),
+                        "username": self.username(tag.user_id),
+                        "profile_pic_url": format!("{FAKE_CDN}/profiles/{}.jpg", tag.user_id),
+                    },
+                    "x": tag.x,
+                    "y": tag.y,
+                }})
+            })
+            .collect();
+
+        let display_url = format!("{FAKE_CDN}/medias/{}.jpg", post.id);
+
+        json!({
+            "__typename": "GraphImage",
+            "id": post.id.to_string(),
+            "shortcode": post.id.to_shortcode().to_string(),
+            "edge_media_to_caption": { "edges": captions },
+            "edge_media_to_comment": {
+                "count": post.comments.len(),
+                "page_info": page_info(None),
+                "edges": comments,
+            },
+            "comments_disabled": false,
+            "dimensions": { "height": post.height, "width": post.width },
+            "display_url": display_url,
+            "display_resources": [{
+                "src": display_url,
+                "config_width": post.width,
+                "config_height": post.height,
+            }],
+            "edge_media_preview_like": { "count": post.like_count },
+            "edge_media_to_tagged_user": { "edges": user_tags },
+            "is_video": false,
+            "location": post.location.as_ref().map(|location| json!({
+                "id": location.id,
+                "name": location.name,
+            })),
+            "media_preview": null,
+            "owner": {
+                "id": post.owner.to_string(),
+                "username": self.username(post.owner),
+            },
+            "taken_at_timestamp": post.taken_at,
+            "thumbnail_src": display_url,
+            "tracking_token": "",
+        })
+    }
+
+    fn reel_user_json(&self, user_id: UserId) -> Value {
+        let user = self.users.get(&user_id);
+
+        json!({
+            "pk": user_id.to_string(),
+            "username": self.username(user_id),
+            "full_name": user.map(|user| user.full_name.clone()).unwrap_or_default(),
+            "profile_pic_url": format!("{FAKE_CDN}/profiles/{user_id}.jpg"),
+            "is_verified": user.is_some_and(|user| user.is_verified),
+        })
+    }
+
+    fn story_json(story: &FakeStory) -> Value {
+        json!({
+            "id": format!("{}_{}", story.id, story.owner),
+            "pk": story.id.to_string(),
+            "taken_at": story.taken_at,
+            "expiring_at": story.taken_at.as_secs() + STORY_LIFETIME,
+            "original_width": 1080,
+            "original_height": 1920,
+            "image_versions2": { "candidates": [{
+                "url": format!("{FAKE_CDN}/stories/{}.jpg", story.id),
+                "width": 1080,
+                "height": 1920,
+            }]},
+        })
+    }
+
+    /// Reel of the live stories of a user, `None` when there is none
+    fn reel_json(&self, owner: UserId, with_items: bool) -> Option<Value> {
+        let stories = self.live_stories(owner);
+        let latest = stories.iter().map(|story| story.taken_at).max()?;
+        let seen = self
+            .viewer
+            .and_then(|viewer| self.seen.get(&(viewer, owner)))
+            .copied()
+            .unwrap_or_default();
+
+        let items: Vec<Value> = if with_items {
+            stories.into_iter().map(Self::story_json).collect()
+        } else {
+            Vec::new()
+        };
+
+        Some(json!({
+            "id": owner.to_string(),
+            "latest_reel_media": latest,
+            "seen": seen,
+            "expiring_at": latest.as_secs() + STORY_LIFETIME,
+            "user": self.reel_user_json(owner),
+            "items": items,
+        }))
+    }
+
+    fn user_infos_json(&self, username: &str) -> Result<Value, UserInfosError> {
+        self.users
+            .values()
+            .find(|user| user.username == username)
+            .map(|user| self.user_json(user))
+            .ok_or(UserInfosError::NotFound)
+    }
+
+    fn user_feed_json(
+        &self,
+        user_id: UserId,
+        pagination: &PaginationOptions<'_>,
+    ) -> Result<Value, ClientError> {
+        let user = self.users.get(&user_id).ok_or(ClientError::NotFound)?;
+        let posts = self.posts_of(&[user_id]);
+        let count = posts.len();
+
+        // The posts of a private account are hidden, not their count
+        let (page, next_cursor) = if self.can_see(user) {
+            paginate(posts, pagination.count, pagination.after)
+        } else {
+            (Vec::new(), None)
+        };
+
+        let edges: Vec<Value> = page
+            .into_iter()
+            .map(|post| json!({ "node": self.media_json(post) }))
+            .collect();
+
+        Ok(json!({
+            "count": count,
+            "page_info": page_info(next_cursor.as_deref()),
+            "edges": edges,
+        }))
+    }
+
+    /// Post visible by the viewer
+    fn visible_post(&self, media_id: MediaId) -> Result<&FakePost, ClientError> {
+        self.posts
+            .get(&media_id)
+            .filter(|post| !post.archived || self.viewer == Some(post.owner))
+            .filter(|post| {
+                self.users
+                    .get(&post.owner)
+                    .is_none_or(|owner| self.can_see(owner))
+            })
+            .ok_or(ClientError::NotFound)
+    }
+
+    fn media_infos_json(&self, media_id: MediaId) -> Result<Value, ClientError> {
+        self.visible_post(media_id)
+            .map(|post| self.media_json(post))
+    }
+
+    fn media_comments_json(
+        &self,
+        media_id: MediaId,
+        pagination: &PaginationOptions<'_>,
+    ) -> Result<Value, ClientError> {
+        let post = self.visible_post(media_id)?;
+        let comments: Vec<&FakeComment> = post.comments.iter().collect();
+
+        let (page, next_cursor) = paginate(comments, pagination.count, pagination.after);
+
+        let edges: Vec<Value> = page
+            .into_iter()
+            .map(|comment| json!({ "node": self.comment_json(comment) }))
+            .collect();
+
+        Ok(json!({
+            "count": post.comments.len(),
+            "page_info": page_info(next_cursor.as_deref()),
+            "edges": edges,
+        }))
+    }
+
+    fn timeline_json(&self, options: &FetchTimelineFeedOptions<'_>) -> Result<Value, ClientError> {
+        let viewer = self.viewer()?;
+        let mut owners = vec![viewer];
+
+        owners.extend(self.followed_by(viewer));
+
+        let (page, next_cursor) = paginate(self.posts_of(&owners), options.count, options.after);
+
+        let edges: Vec<Value> = page
+            .into_iter()
+            .map(|post| json!({ "node": self.media_json(post) }))
+            .collect();
+
+        Ok(json!({
+            "page_info": page_info(next_cursor.as_deref()),
+            "edges": edges,
+        }))
+    }
+
+    fn reels_tray_json(&self) -> Result<Value, ClientError> {
+        let viewer = self.viewer()?;
+
+        let tray: Vec<Value> = self
+            .followed_by(viewer)
+            .filter_map(|followed| self.reel_json(followed, false))
+            .collect();
+
+        Ok(json!({ "tray": tray }))
+    }
+
+    fn reels_feed_json(&self, user_ids: &[UserId]) -> Result<Value, ClientError> {
+        self.viewer()?;
+
+        Ok(user_ids
+            .iter()
+            .filter_map(|user_id| self.reel_json(*user_id, true))
+            .collect())
+    }
+
+    fn highlights_json(&self, user_id: UserId) -> Result<Value, ClientError> {
+        self.viewer()?;
+
+        Ok(self
+            .highlights
+            .iter()
+            .filter(|highlight| highlight.owner == user_id)
+            .map(|highlight| {
+                let stories: Vec<&FakeStory> = highlight
+                    .stories
+                    .iter()
+                    .filter_map(|id| self.stories.get(id))
+                    .collect();
+
+                json!({
+                    "id": highlight.id,
+                    "title": highlight.title,
+                    "latest_reel_media": stories.iter().map(|story| story.taken_at).max(),
+                    "user": self.reel_user_json(user_id),
+                    "items": stories.into_iter().map(Self::story_json).collect::<Vec<_>>(),
+                })
+            })
+            .collect())
+    }
+
+    fn delete_post(&mut self, media_id: MediaId) -> Result<(), ClientError> {
+        self.own_post(media_id)?;
+        self.posts.remove(&media_id);
+
+        Ok(())
+    }
+
+    /// Remember the latest story of each owner seen by the viewer
+    fn mark_seen(&mut self, items: &[&ReelItem]) -> Result<Vec<String>, ClientError> {
+        let viewer = self.viewer()?;
+        let mut item_ids = Vec::with_capacity(items.len());
+
+        for item in items {
+            let owner_id = item.owner_id().ok_or_else(|| {
+                ClientError::ActionFailed(format!("Unable to find the owner of story {}", item.id))
+            })?;
+
+            let seen = self.seen.entry((viewer, owner_id)).or_default();
+
+            *seen = (*seen).max(item.taken_at);
+
+            item_ids.push(item.id.clone());
+        }
+
+        Ok(item_ids)
+    }
+}
+
+#[async_trait]
+impl FetchUserInfos for FakeInstagram {
+    async fn fetch_user_infos(&self, username: &str) -> Result<UserInfos, UserInfosError> {
+        let user = self.state().user_infos_json(username)?;
+
+        serde_json::from_value(user).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl FetchUserFeed for FakeInstagram {
+    async fn fetch_user_feed(
+        &self,
+        user_id: UserId,
+        options: Option<FetchUserFeedOptions<'_>>,
+    ) -> Result<UserFeed, ClientError> {
+        let pagination = options.unwrap_or_default().pagination;
+        let feed = self.state().user_feed_json(user_id, &pagination)?;
+
+        serde_json::from_value(feed).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl FetchMediaInfos for FakeInstagram {
+    async fn fetch_media_infos(&self, media_id: MediaId) -> Result<Media, ClientError> {
+        let media = self.state().media_infos_json(media_id)?;
+
+        serde_json::from_value(media).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl FetchMediaComments for FakeInstagram {
+    async fn fetch_media_comments(
+        &self,
+        media_id: MediaId,
+        options: Option<FetchMediaCommentsOptions<'_>>,
+    ) -> Result<MediaComments, ClientError> {
+        let pagination = options.unwrap_or_default().pagination;
+        let comments = self.state().media_comments_json(media_id, &pagination)?;
+
+        serde_json::from_value(comments).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl DeleteMedia for FakeInstagram {
+    async fn delete_media(&self, media_id: MediaId) -> Result<MediaDeletion, ClientError> {
+        self.state().delete_post(media_id)?;
+
+        Ok(MediaDeletion { did_delete: true })
+    }
+}
+
+#[async_trait]
+impl ArchiveMedia for FakeInstagram {
+    async fn archive_media(&self, media_id: MediaId) -> Result<MediaArchiving, ClientError> {
+        self.state().own_post(media_id)?.archived = true;
+
+        Ok(MediaArchiving {
+            media_id,
+            archived: true,
+        })
+    }
+
+    async fn unarchive_media(&self, media_id: MediaId) -> Result<MediaArchiving, ClientError> {
+        self.state().own_post(media_id)?.archived = false;
+
+        Ok(MediaArchiving {
+            media_id,
+            archived: false,
+        })
+    }
+}
+
+#[async_trait]
+impl EditMediaCaption for FakeInstagram {
+    async fn edit_media_caption(
+        &self,
+        media_id: MediaId,
+        caption: &str,
+    ) -> Result<MediaCaptionEdition, ClientError> {
+        let caption = Some(caption.to_string()).filter(|caption| !caption.is_empty());
+
+        self.state()
+            .own_post(media_id)?
+            .caption
+            .clone_from(&caption);
+
+        Ok(MediaCaptionEdition { media_id, caption })
+    }
+}
+
+#[async_trait]
+impl PostPhoto for FakeInstagram {
+    async fn post_photo(
+        &self,
+        photo: Photo<'_>,
+        options: Option<PostPhotoOptions<'_>>,
+    ) -> Result<Media, ClientError> {
+        let owner = self.state().viewer()?;
+        let options = options.unwrap_or_default();
+
+        let photo = match photo {
+            Photo::Jpeg(bytes) => bytes,
+            Photo::Path(path) => tokio::fs::read(path)
+                .await
+                .map_err(|error| ClientError::UnableToReadPhoto(error.into()))?,
+        };
+
+        let (width, height) = upload::jpeg_dimensions(&photo).ok_or(ClientError::InvalidPhoto)?;
+
+        let post = FakePost {
+            id: MediaId::new(0),
+            owner,
+            caption: options.caption.map(ToString::to_string),
+            taken_at: Timestamp::now(),
+            width,
+            height,
+            like_count: 0,
+            archived: false,
+            location: options.location.map(|location| FakeLocation {
+                id: location.id().to_string(),
+                name: location.name().to_string(),
+            }),
+            user_tags: options.user_tags,
+            comments: Vec::new(),
+        };
+
+        let media_id = self.state().add_post(post);
+        let media = self.state().media_infos_json(media_id)?;
+
+        serde_json::from_value(media).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl FetchTimelineFeed for FakeInstagram {
+    async fn fetch_timeline_feed(
+        &self,
+        options: Option<FetchTimelineFeedOptions<'_>>,
+    ) -> Result<TimelineFeed, ClientError> {
+        let feed = self.state().timeline_json(&options.unwrap_or_default())?;
+
+        serde_json::from_value(feed).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl FetchReelsTray for FakeInstagram {
+    async fn fetch_reels_tray(&self) -> Result<ReelsTray, ClientError> {
+        let tray = self.state().reels_tray_json()?;
+
+        serde_json::from_value(tray).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl FetchReelsFeed for FakeInstagram {
+    async fn fetch_reels_feed(&self, user_ids: &[UserId]) -> Result<Vec<Reel>, ClientError> {
+        let reels = self.state().reels_feed_json(user_ids)?;
+
+        serde_json::from_value(reels).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl FetchHighlightReels for FakeInstagram {
+    async fn fetch_highlight_reels(&self, user_id: UserId) -> Result<Vec<Highlight>, ClientError> {
+        let highlights = self.state().highlights_json(user_id)?;
+
+        serde_json::from_value(highlights).map_err(Into::into)
+    }
+}
+
+#[async_trait]
+impl MarkStoriesAsSeen for FakeInstagram {
+    async fn mark_stories_as_seen(&self, items: &[&ReelItem]) -> Result<StoriesSeen, ClientError> {
+        let seen_at = Timestamp::now();
+        let item_ids = self.state().mark_seen(items)?;
+
+        Ok(StoriesSeen { item_ids, seen_at })
+    }
+}
@@ -0,0 +1,318 @@
+use async_trait::async_trait;
+use std::fmt;
+use std::sync::Arc;
+
+pub use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+pub use reqwest::{Method, StatusCode};
+
+/// Request sent by a client to Instagram
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Request {
+    pub method: Method,
+    /// Url of the endpoint, with its query string
+    pub url: String,
+    pub headers: HeaderMap,
+    /// Body of the request, empty when there is none
+    pub body: Vec<u8>,
+}
+
+impl Request {
+    #[must_use]
+    pub fn new(method: Method, url: &str) -> Self {
+        Self {
+            method,
+            url: url.to_string(),
+            headers: HeaderMap::new(),
+            body: Vec::new(),
+        }
+    }
+
+    #[must_use]
+    pub fn get(url: &str) -> Self {
+        Self::new(Method::GET, url)
+    }
+
+    #[must_use]
+    pub fn post(url: &str) -> Self {
+        Self::new(Method::POST, url)
+    }
+
+    /// Append the parameters to the query string of the url
+    #[must_use]
+    pub fn query(mut self, query: &[(&str, &str)]) -> Self {
+        if query.is_empty() {
+            return self;
+        }
+
+        if let Ok(query) = serde_urlencoded::to_string(query) {
+            let separator = if self.url.contains('?') { '&' } else { '?' };
+
+            self.url = format!("{}{separator}{query}", self.url);
+        }
+
+        self
+    }
+
+    /// Add a header, an invalid name or value is ignored
+    #[must_use]
+    pub fn header(mut self, name: &str, value: &str) -> Self {
+        if let (Ok(name), Ok(value)) = (
+            HeaderName::from_bytes(name.as_bytes()),
+            HeaderValue::from_str(value),
+        ) {
+            self.headers.insert(name, value);
+        }
+
+        self
+    }
+
+    /// Send the fields as an url encoded form
+    #[must_use]
+    pub fn form(mut self, form: &[(&str, &str)]) -> Self {
+        self.body = serde_urlencoded::to_string(form)
+            .unwrap_or_default()
+            .into_bytes();
+
+        self.header("content-type", "application/x-www-form-urlencoded")
+    }
+
+    #[must_use]
+    pub fn body(mut self, body: Vec<u8>) -> Self {
+        self.body = body;
+
+        self
+    }
+}
+
+/// Response received from Instagram, whatever its status
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Response {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    pub body: Vec<u8>,
+}
+
+impl Response {
+    /// Cookies set by the response, as `(name, value)`
+    #[must_use]
+    pub fn cookies(&self) -> Vec<(String, String)> {
+        self.headers
+            .get_all(reqwest::header::SET_COOKIE)
+            .iter()
+            .filter_map(|header| header.to_str().ok())
+            .filter_map(|header| cookie::Cookie::parse(header).ok())
+            .map(|cookie| (cookie.name().to_string(), cookie.value().to_string()))
+            .collect()
+    }
+}
+
+/// Response whose body is read chunk by chunk, to write large files as they're received
+#[derive(Debug)]
+pub struct StreamingResponse {
+    pub status: StatusCode,
+    pub headers: HeaderMap,
+    body: StreamingBody,
+}
+
+#[derive(Debug)]
+enum StreamingBody {
+    /// Body already read, given as a single chunk
+    Buffered(Option<Vec<u8>>),
+    Reqwest(Box<reqwest::Response>),
+}
+
+impl StreamingResponse {
+    /// Response streaming the body of a `reqwest` response
+    #[must_use]
+    pub fn from_reqwest(response: reqwest::Response) -> Self {
+        Self {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: StreamingBody::Reqwest(Box::new(response)),
+        }
+    }
+
+    /// Next chunk of the body, `None` once the whole body was read
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if the connection failed while the body was read.
+    pub async fn chunk(&mut self) -> Result<Option<Vec<u8>>, TransportError> {
+        match self.body {
+            StreamingBody::Buffered(ref mut body) => Ok(body.take()),
+            StreamingBody::Reqwest(ref mut response) => {
+                Ok(response.chunk().await?.map(|chunk| chunk.to_vec()))
+            }
+        }
+    }
+}
+
+impl From<Response> for StreamingResponse {
+    fn from(response: Response) -> Self {
+        Self {
+            status: response.status,
+            headers: response.headers,
+            body: StreamingBody::Buffered(Some(response.body)),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TransportError {
+    /// Instagram couldn't be reached or the response couldn't be read
+    Connection(String),
+    /// The request couldn't be built, Instagram wasn't reached
+    InvalidRequest(String),
+    /// The request isn't in the cassette being replayed
+    NotRecorded(String),
+}
+
+impl From<reqwest::Error> for TransportError {
+    fn from(error: reqwest::Error) -> Self {
+        if error.is_builder() {
+            Self::InvalidRequest(error.to_string())
+        } else {
+            Self::Connection(error.to_string())
+        }
+    }
+}
+
+/// HTTP layer sending the requests of a client
+///
+/// The clients go through [`ReqwestTransport`] unless another one is given with
+/// [`Client::with_transport`](crate::web_api::Client::with_transport), to instrument the
+/// requests, send them through a proxy or answer them in process.
+///
+/// # Examples
+///
+/// ```rust
+/// use async_trait::async_trait;
+/// use instagram::web_api::transport::{
+///     ReqwestTransport, Request, Response, Transport, TransportError,
+/// };
+/// use instagram::web_api::Client;
+///
+/// struct Logged(ReqwestTransport);
+///
+/// #[async_trait]
+/// impl Transport for Logged {
+///     async fn send(&self, request: Request) -> Result<Response, TransportError> {
+///         println!("{} {}", request.method, request.url);
+///
+///         self.0.send(request).await
+///     }
+/// }
+///
+/// let client = Client::new().with_transport(Logged(ReqwestTransport::new()));
+/// ```
+#[async_trait]
+pub trait Transport: Send + Sync {
+    /// Send the request, a response is returned whatever its status
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no response was received.
+    async fn send(&self, request: Request) -> Result<Response, TransportError>;
+
+    /// Send the request and return the response before its body is read, used to download the
+    /// files of the medias
+    ///
+    /// The default implementation reads the whole body with [`send`](Transport::send).
+    ///
+    /// # Errors
+    ///
+    /// Will return `Err` if no response was received.
+    async fn send_streaming(&self, request: Request) -> Result<StreamingResponse, TransportError> {
+        self.send(request).await.map(Into::into)
+    }
+}
+
+/// Transport sending the requests with `reqwest`
+#[derive(Debug, Clone, Default)]
+pub struct ReqwestTransport {
+    client: reqwest::Client,
+}
+
+impl ReqwestTransport {
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Send the requests with a configured `reqwest` client, to set a proxy or timeouts
+    #[must_use]
+    pub const fn from_client(client: reqwest::Client) -> Self {
+        Self { client }
+    }
+
+    fn request(&self, request: Request) -> reqwest::RequestBuilder {
+        let builder = self
+            .client
+            .request(request.method, &request.url)
+            .headers(request.headers);
+
+        if request.body.is_empty() {
+            builder
+        } else {
+            builder.body(request.body)
+        }
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        let response = self.request(request).send().await?;
+
+        Ok(Response {
+            status: response.status(),
+            headers: response.headers().clone(),
+            body: response.bytes().await?.to_vec(),
+        })
+    }
+
+    async fn send_streaming(&self, request: Request) -> Result<StreamingResponse, TransportError> {
+        let response = self.request(request).send().await?;
+
+        Ok(StreamingResponse::from_reqwest(response))
+    }
+}
+
+/// Transport of a client, shared with the authenticated client created on login
+#[derive(Clone)]
+pub(crate) struct SharedTransport(Arc<dyn Transport>);
+
+impl SharedTransport {
+    pub(crate) fn new<T: Transport + 'static>(transport: T) -> Self {
+        Self(Arc::new(transport))
+    }
+
+    pub(crate) async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        self.0.send(request).await
+    }
+
+    pub(crate) async fn send_streaming(
+        &self,
+        request: Request,
+    ) -> Result<StreamingResponse, TransportError> {
+        self.0.send_streaming(request).await
+    }
+}
+
+impl Default for SharedTransport {
+    fn default() -> Self {
+        Self::new(ReqwestTransport::new())
+    }
+}
+
+impl fmt::Debug for SharedTransport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str("Transport")
+    }
+}
+
+impl PartialEq for SharedTransport {
+    fn eq(&self, other: &Self) -> bool {
+        Arc::ptr_eq(&self.0, &other.0)
+    }
+}
@@ -4,7 +4,19 @@ extern crate lazy_static;
 extern crate assert_impl;
 
 mod web_api_client {
+    mod archive;
+    mod authenticated_actions;
     mod authenticated_client;
+    #[cfg(feature = "blocking")]
+    mod blocking_client;
+    mod cassette;
     mod create_client;
+    mod download;
+    #[cfg(feature = "testing")]
+    mod fake_instagram;
+    mod rate_limit;
+    mod rate_limited;
+    mod retry;
+    mod transport;
     mod unauthenticated_client;
 }
@@ -0,0 +1,184 @@
+extern crate instagram;
+
+use mockito::mock;
+use mockito::Matcher;
+
+use instagram::web_api::archive::{Archiver, Manifest, MANIFEST_FILE};
+use instagram::web_api::Client;
+use std::path::Path;
+
+fn with_local_cdn(fixture: &str) -> String {
+    let cdn = format!("{}/archive-cdn", mockito::server_url());
+
+    ::std::fs::read_to_string(fixture)
+        .unwrap()
+        .replace("https://scontent-cdg2-1.cdninstagram.com", &cdn)
+        .replace("https://scontent-cdt1-1.cdninstagram.com", &cdn)
+}
+
+#[tokio::test]
+async fn test_archive_is_incremental() {
+    let fixture_infos = with_local_cdn("tests/web_api_client/response_user_info.json");
+    let fixture_feed = with_local_cdn("tests/web_api_client/response_user_feed.json");
+
+    let last_page = with_local_cdn("tests/web_api_client/response_user_feed_carousel_reel.json");
+
+    let fixture_comments = with_local_cdn("tests/web_api_client/response_media_comments.json");
+
+    let mut last_comments: serde_json::Value = serde_json::from_str(&fixture_comments).unwrap();
+    let comments = last_comments
+        .pointer_mut("/data/shortcode_media/edge_media_to_parent_comment")
+        .unwrap();
+
+    comments["edges"][0]["node"]["id"] = "17925306244370954".into();
+    comments["edges"][0]["node"]["text"] = "@visual_ade_photography thanks!".into();
+    comments["page_info"] = serde_json::json!({"has_next_page": false, "end_cursor": null});
+
+    let m_user_info = mock("GET", "/archive/freyskeyd")
+        .match_query(Matcher::UrlEncoded("__a".into(), "1".into()))
+        .with_status(200)
+        .with_body(&fixture_infos)
+        .expect(3)
+        .create();
+
+    let m_first_page = mock("GET", "/archive/graphql")
+        .match_query(Matcher::UrlEncoded(
+            "variables".into(),
+            r#"{"id":"8999249","first":50,"after":null}"#.into(),
+        ))
+        .with_status(200)
+        .with_body(&fixture_feed)
+        .expect(3)
+        .create();
+
+    let m_last_page = mock("GET", "/archive/graphql")
+        .match_query(Matcher::UrlEncoded(
+            "variables".into(),
+            r#"{"id":"8999249","first":50,"after":"QVFCczR4Tl8ybGVWVlBZVm96WDliZWZlRnpzUVJhakZ1a0JTbzR0YjFCNXhZZTRnRUFPWGFoOXFKa0xPNEM5Q211WmxHeHFPUXEtZzRnUWs2WHBLRmFPZA=="}"#.into(),
+        ))
+        .with_status(200)
+        .with_body(&last_page)
+        .expect(1)
+        .create();
+
+    // Comments of each media, on the first run and on the run refreshing them
+    let m_first_comments = mock("GET", "/archive/graphql")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(
+                "query_hash".into(),
+                "bc3296d1ce80a24b1b6e40b1e72903f5".into(),
+            ),
+            Matcher::Regex("%22after%22%3Anull".into()),
+        ]))
+        .with_status(200)
+        .with_body(&fixture_comments)
+        .expect(28)
+        .create();
+
+    let m_last_comments = mock("GET", "/archive/graphql")
+        .match_query(Matcher::AllOf(vec![
+            Matcher::UrlEncoded(
+                "query_hash".into(),
+                "bc3296d1ce80a24b1b6e40b1e72903f5".into(),
+            ),
+            Matcher::Regex("%22after%22%3A%22QVFDYmJ0X2Y4".into()),
+        ]))
+        .with_status(200)
+        .with_body(last_comments.to_string())
+        .expect(28)
+        .create();
+
+    let m_images = mock("GET", Matcher::Regex(r"^/archive-cdn/.*\.jpg".into()))
+        .with_status(200)
+        .with_header("content-type", "image/jpeg")
+        .with_body("jpeg bytes")
+        .expect(13)
+        .create();
+
+    let m_videos = mock("GET", Matcher::Regex(r"^/archive-cdn/.*\.mp4".into()))
+        .with_status(200)
+        .with_header("content-type", "video/mp4")
+        .with_body("mp4 bytes")
+        .expect(2)
+        .create();
+
+    let dir = std::env::temp_dir().join("instagram-test-archive");
+    let _ = std::fs::remove_dir_all(&dir);
+
+    let api_url = format!("{}/archive", mockito::server_url());
+    let graphql_api_url = format!("{}/archive/graphql", mockito::server_url());
+    let client = Client::new_with_url(&api_url, &graphql_api_url);
+    let archiver = Archiver::new(&client, &dir);
+
+    let manifest = archiver.archive("freyskeyd").await.unwrap();
+
+    assert_eq!(manifest.username, "freyskeyd");
+    assert!(manifest.feed_complete);
+    assert_eq!(manifest.medias.len(), 14);
+
+    let carousel = &manifest.medias["B8ce-zwHd0B"];
+
+    assert_eq!(carousel.files.len(), 2);
+    assert_eq!(
+        carousel.files[1].path,
+        Path::new("medias/B8ce-zwHd0B/2245000000000000003.mp4")
+    );
+
+    let root = dir.join("freyskeyd");
+
+    assert!(root.join("profile.json").is_file());
+    assert!(root.join("medias/B8690canc8f.json").is_file());
+
+    let comments = carousel.comments.as_ref().unwrap();
+    let saved_comments: serde_json::Value =
+        serde_json::from_slice(&std::fs::read(root.join(&comments.path)).unwrap()).unwrap();
+
+    assert_eq!(comments.path, Path::new("medias/B8ce-zwHd0B.comments.json"));
+    assert_eq!(comments.count, 2);
+    assert_eq!(saved_comments[0]["text"], "Beautiful portrait.");
+    assert_eq!(saved_comments[1]["text"], "@visual_ade_photography thanks!");
+    assert_eq!(
+        std::fs::read_to_string(root.join(&carousel.files[1].path)).unwrap(),
+        "mp4 bytes"
+    );
+
+    let manifest = archiver.archive("freyskeyd").await.unwrap();
+    let saved: Manifest =
+        serde_json::from_slice(&std::fs::read(root.join(MANIFEST_FILE)).unwrap()).unwrap();
+
+    assert_eq!(manifest.medias.len(), 14);
+    assert_eq!(saved.medias.len(), 14);
+    assert!(saved.medias.values().all(|media| media
+        .comments
+        .as_ref()
+        .is_some_and(|comments| comments.count == 2)));
+    assert_eq!(
+        saved.medias["B8690canc8f"].files[0].sha256,
+        "1b48e21282963dfba2ffff3a4c331471242fe42fd0a51161e56df72085c445c9"
+    );
+
+    let refreshed = Archiver::new(&client, &dir)
+        .set_refresh_comments(true)
+        .archive("freyskeyd")
+        .await
+        .unwrap();
+
+    assert_eq!(refreshed.medias.len(), 14);
+    assert_eq!(
+        refreshed.medias["B8ce-zwHd0B"]
+            .comments
+            .as_ref()
+            .map(|comments| comments.count),
+        Some(2)
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    m_user_info.assert();
+    m_first_page.assert();
+    m_last_page.assert();
+    m_first_comments.assert();
+    m_last_comments.assert();
+    m_images.assert();
+    m_videos.assert();
+}
@@ -0,0 +1,434 @@
+extern crate instagram;
+
+use mockito::mock;
+use mockito::Matcher;
+
+use instagram::web_api::behaviour::*;
+use instagram::web_api::AuthenticatedClient;
+use instagram::web_api::Client;
+use instagram::web_api::ClientError;
+use instagram::web_api::Credentials;
+
+use instagram::web_api::error::RateLimitKind;
+
+use instagram::web_api::domain::{MediaId, TimelineFeed, TimelineItem, Timestamp, UserId};
+use instagram::web_api::options::{Photo, PostPhotoOptions, UserTag};
+
+async fn logged_in_client() -> AuthenticatedClient {
+    let fixture = "{\"authenticated\": true, \"user\": true, \"userId\": \"8343444274\", \"oneTapPrompt\": false, \"status\": \"ok\"}";
+
+    let fixture_init_rollout_hash: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_init_rollout.html").unwrap();
+
+    let _m_root = mock("GET", "/")
+        .with_body(fixture_init_rollout_hash)
+        .with_status(200)
+        .create();
+
+    let _m_login = mock("POST", "/accounts/login/ajax/")
+        .with_status(200)
+        .with_header("set-cookie", "csrftoken=logged_in_token; Path=/")
+        .with_header("set-cookie", "sessionid=some_session; Path=/")
+        .with_body(fixture)
+        .create();
+
+    Client::new_with_url(&mockito::server_url(), &mockito::server_url())
+        .login(&Credentials {
+            username: "user",
+            password: "passw",
+        })
+        .await
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_session_is_resumed() {
+    let client = logged_in_client().await;
+    let session = client.session();
+
+    assert_eq!(session.csrf_token.as_deref(), Some("logged_in_token"));
+    assert_eq!(session.cookie("sessionid"), Some("some_session"));
+
+    let saved = serde_json::to_string(&session).unwrap();
+    let resumed = AuthenticatedClient::from_session(
+        Client::new_with_url(&mockito::server_url(), &mockito::server_url()),
+        serde_json::from_str(&saved).unwrap(),
+    );
+
+    assert_eq!(resumed.session(), session);
+}
+
+#[tokio::test]
+async fn test_delete_media() {
+    let client = logged_in_client().await;
+
+    let m_delete = mock("POST", "/create/2250382848586272543/delete/")
+        .match_header("accept-language", "en-US")
+        .match_header("x-csrftoken", "logged_in_token")
+        .match_header("cookie", Matcher::Regex("sessionid=some_session".into()))
+        .with_status(200)
+        .with_body("{\"did_delete\": true, \"status\": \"ok\"}")
+        .expect(1)
+        .create();
+
+    let deletion = client
+        .delete_media(MediaId::new(2_250_382_848_586_272_543))
+        .await
+        .unwrap();
+
+    assert!(deletion.did_delete);
+    m_delete.assert();
+}
+
+#[tokio::test]
+async fn test_action_blocked() {
+    let client = logged_in_client().await;
+
+    let m_delete = mock("POST", "/create/2250382848586272544/delete/")
+        .with_status(400)
+        .with_body("{\"message\": \"feedback_required\", \"spam\": true, \"feedback_title\": \"Action Blocked\", \"feedback_message\": \"This action was blocked. Please try again later.\", \"status\": \"fail\"}")
+        .expect(1)
+        .create();
+
+    let deletion = client
+        .delete_media(MediaId::new(2_250_382_848_586_272_544))
+        .await;
+
+    match deletion {
+        Err(ClientError::RateLimited(limited)) => {
+            assert_eq!(limited.kind(), RateLimitKind::FeedbackRequired);
+            assert_eq!(
+                limited.message(),
+                "This action was blocked. Please try again later."
+            );
+        }
+        other => panic!("expected an action block, got {:?}", other),
+    }
+
+    m_delete.assert();
+}
+
+#[tokio::test]
+async fn test_archive_and_unarchive_media() {
+    let client = logged_in_client().await;
+
+    let m_archive = mock("POST", "/api/v1/media/2250382848586272543/only_me/")
+        .with_status(200)
+        .with_body("{\"status\": \"ok\"}")
+        .expect(1)
+        .create();
+
+    let m_unarchive = mock("POST", "/api/v1/media/2250382848586272543/undo_only_me/")
+        .with_status(400)
+        .with_body("{\"message\": \"Media not found\", \"status\": \"fail\"}")
+        .expect(1)
+        .create();
+
+    let media_id: MediaId = "2250382848586272543".parse().unwrap();
+    let archiving = client.archive_media(media_id).await.unwrap();
+
+    assert!(archiving.archived);
+    assert_eq!(archiving.media_id, media_id);
+
+    assert_eq!(
+        Err(ClientError::ActionFailed("Media not found".into())),
+        client.unarchive_media(media_id).await
+    );
+
+    m_archive.assert();
+    m_unarchive.assert();
+}
+
+#[tokio::test]
+async fn test_edit_media_caption() {
+    let client = logged_in_client().await;
+
+    let m_edit = mock("POST", "/api/v1/media/2250382848586272543/edit_media/")
+        .match_body(Matcher::UrlEncoded(
+            "caption_text".into(),
+            "Fixed caption #bnwportrait".into(),
+        ))
+        .with_status(200)
+        .with_body("{\"media\": {\"caption\": {\"text\": \"Fixed caption #bnwportrait\"}}, \"status\": \"ok\"}")
+        .expect(1)
+        .create();
+
+    let edition = client
+        .edit_media_caption(
+            MediaId::new(2_250_382_848_586_272_543),
+            "Fixed caption #bnwportrait",
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(
+        edition.caption.as_deref(),
+        Some("Fixed caption #bnwportrait")
+    );
+    m_edit.assert();
+}
+
+#[tokio::test]
+async fn test_post_photo() {
+    let client = logged_in_client().await;
+
+    let jpeg = vec![
+        0xFF, 0xD8, 0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x04, 0x38, 0x04, 0x38, 0x01, 0x01, 0x11, 0x00,
+    ];
+
+    let m_upload = mock(
+        "POST",
+        Matcher::Regex(r"^/rupload_igphoto/fb_uploader_\d+$".into()),
+    )
+    .match_header("x-entity-type", "image/jpeg")
+    .match_header(
+        "x-instagram-rupload-params",
+        Matcher::Regex("\"upload_media_width\":1080".into()),
+    )
+    .with_status(200)
+    .with_body("{\"upload_id\": \"1588409492381\", \"status\": \"ok\"}")
+    .expect(1)
+    .create();
+
+    let m_configure = mock("POST", "/create/configure/")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("upload_id".into(), "1588409492381".into()),
+            Matcher::UrlEncoded("caption".into(), "Nouveau triptyque".into()),
+            Matcher::UrlEncoded(
+                "usertags".into(),
+                "{\"in\":[{\"user_id\":\"8999249\",\"position\":[0.5,0.25]}]}".into(),
+            ),
+        ]))
+        .with_status(200)
+        .with_body(
+            r#"{"media": {"pk": 2250382848586272543, "code": "B8690canc8f", "taken_at": 1582486564,
+            "original_width": 1080, "original_height": 1080,
+            "image_versions2": {"candidates": [
+                {"url": "https://scontent.cdninstagram.com/1080.jpg", "width": 1080, "height": 1080},
+                {"url": "https://scontent.cdninstagram.com/150.jpg", "width": 150, "height": 150}
+            ]},
+            "caption": {"text": "Nouveau triptyque"},
+            "user": {"pk": 8999249, "username": "freyskeyd"}}, "status": "ok"}"#,
+        )
+        .expect(1)
+        .create();
+
+    let options = PostPhotoOptions::default()
+        .set_caption("Nouveau triptyque")
+        .add_user_tag(UserTag {
+            user_id: UserId::new(8_999_249),
+            x: 0.5,
+            y: 0.25,
+        });
+
+    let media = client.post_photo(Photo::Jpeg(jpeg), Some(options)).await;
+
+    assert!(media.is_ok(), "{:?}", media);
+
+    assert_eq!(
+        Err(ClientError::InvalidPhoto),
+        client
+            .post_photo(Photo::Jpeg(b"\x89PNG".to_vec()), None)
+            .await
+            .map(|_| ())
+    );
+
+    m_upload.assert();
+    m_configure.assert();
+}
+
+#[tokio::test]
+async fn test_timeline_feed() {
+    let client = logged_in_client().await;
+
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_timeline_feed.json").unwrap();
+
+    let m_timeline = mock("GET", "/")
+        .match_query(Matcher::Regex("query_hash=.*variables=.*".into()))
+        .match_header("cookie", Matcher::Regex("sessionid=some_session".into()))
+        .with_status(200)
+        .with_body(&fixture)
+        .expect(1)
+        .create();
+
+    let timeline = client.fetch_timeline_feed(None).await.unwrap();
+
+    assert_eq!(timeline.items.len(), 4);
+    assert!(matches!(timeline.items[0], TimelineItem::Media(_)));
+    assert!(matches!(timeline.items[1], TimelineItem::Sponsored(_)));
+
+    match &timeline.items[2] {
+        TimelineItem::SuggestedUsers(suggestions) => {
+            assert_eq!(suggestions.users[0].username, "visual_ade_photography")
+        }
+        item => panic!("Expected suggested users, got {:?}", item),
+    }
+
+    assert!(
+        matches!(&timeline.items[3], TimelineItem::Unknown(typename) if typename == "GraphStoriesInFeedItem")
+    );
+
+    m_timeline.assert();
+
+    let mut fixture: serde_json::Value = serde_json::from_str(&fixture).unwrap();
+    let mut feed = fixture
+        .pointer_mut("/data/user/edge_web_feed_timeline")
+        .unwrap()
+        .take();
+
+    feed["edges"][1]["node"]["shortcode"] = "not a shortcode!".into();
+
+    let timeline: TimelineFeed = serde_json::from_value(feed).unwrap();
+
+    assert_eq!(timeline.items.len(), 4);
+    assert!(matches!(timeline.items[0], TimelineItem::Media(_)));
+
+    match &timeline.items[1] {
+        TimelineItem::Invalid { typename, node, .. } => {
+            assert_eq!(typename, "GraphImage");
+            assert_eq!(node["shortcode"], "not a shortcode!");
+        }
+        item => panic!("Expected an invalid item, got {:?}", item),
+    }
+}
+
+#[tokio::test]
+async fn test_reels_tray() {
+    let client = logged_in_client().await;
+
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_reels_tray.json").unwrap();
+
+    let m_tray = mock("GET", "/api/v1/feed/reels_tray/")
+        .with_status(200)
+        .with_body(&fixture)
+        .expect(1)
+        .create();
+
+    let tray = client.fetch_reels_tray().await.unwrap();
+
+    assert_eq!(tray.reels.len(), 2);
+    assert_eq!(tray.reels[0].user.username, "freyskeyd");
+    assert_eq!(tray.reels[0].seen, Timestamp::from_secs(1_588_400_000));
+    assert!(tray.reels[0].items[1].is_video());
+    assert_eq!(tray.unseen().count(), 1);
+
+    m_tray.assert();
+}
+
+#[tokio::test]
+async fn test_reels_feed_is_batched() {
+    let client = logged_in_client().await;
+
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_reels_media.json").unwrap();
+
+    let m_first_batch = mock("GET", "/api/v1/feed/reels_media/")
+        .match_query(Matcher::Regex("^reel_ids=8999249&".into()))
+        .with_status(200)
+        .with_body(&fixture)
+        .expect(1)
+        .create();
+
+    let m_second_batch = mock("GET", "/api/v1/feed/reels_media/")
+        .match_query(Matcher::Regex("^reel_ids=50&".into()))
+        .with_status(200)
+        .with_body("{\"reels\": {}, \"status\": \"ok\"}")
+        .expect(1)
+        .create();
+
+    let user_ids: Vec<UserId> = std::iter::once(8_999_249)
+        .chain(1..60)
+        .map(UserId::new)
+        .collect();
+
+    let reels = client.fetch_reels_feed(&user_ids).await.unwrap();
+
+    assert_eq!(reels.len(), 1);
+    assert_eq!(reels[0].user.username, "freyskeyd");
+    assert_eq!(reels[0].items.len(), 2);
+
+    m_first_batch.assert();
+    m_second_batch.assert();
+}
+
+#[tokio::test]
+async fn test_highlight_reels() {
+    let client = logged_in_client().await;
+
+    let fixture_tray: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_highlights_tray.json").unwrap();
+    let fixture_media: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_highlights_media.json").unwrap();
+
+    let m_tray = mock("GET", "/api/v1/highlights/8999249/highlights_tray/")
+        .with_status(200)
+        .with_body(&fixture_tray)
+        .expect(1)
+        .create();
+
+    let m_media = mock("GET", "/api/v1/feed/reels_media/")
+        .match_query(Matcher::Regex(
+            "^reel_ids=highlight%3A17860000000000001&reel_ids=highlight%3A17860000000000002$"
+                .into(),
+        ))
+        .with_status(200)
+        .with_body(&fixture_media)
+        .expect(1)
+        .create();
+
+    let highlights = client
+        .fetch_highlight_reels(UserId::new(8_999_249))
+        .await
+        .unwrap();
+
+    assert_eq!(highlights.len(), 2);
+    assert_eq!(highlights[0].title, "Portraits");
+    assert_eq!(highlights[0].items.len(), 1);
+    assert_eq!(highlights[1].id, "highlight:17860000000000002");
+    assert!(highlights[1].items[1].is_video());
+
+    m_tray.assert();
+    m_media.assert();
+}
+
+#[tokio::test]
+async fn test_mark_stories_as_seen() {
+    let client = logged_in_client().await;
+
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_reels_tray.json").unwrap();
+
+    let m_tray = mock("GET", "/api/v1/feed/reels_tray/")
+        .with_status(200)
+        .with_body(&fixture)
+        .expect(1)
+        .create();
+
+    let m_seen = mock("POST", "/stories/reel/seen")
+        .match_body(Matcher::AllOf(vec![
+            Matcher::UrlEncoded("reelMediaId".into(), "2300000000000000002".into()),
+            Matcher::UrlEncoded("reelMediaOwnerId".into(), "8999249".into()),
+            Matcher::UrlEncoded("reelId".into(), "8999249".into()),
+            Matcher::UrlEncoded("reelMediaTakenAt".into(), "1588409492".into()),
+        ]))
+        .with_status(200)
+        .with_body("{\"status\": \"ok\"}")
+        .expect(1)
+        .create();
+
+    let tray = client.fetch_reels_tray().await.unwrap();
+    let unseen: Vec<_> = tray.reels[0]
+        .items
+        .iter()
+        .filter(|item| item.taken_at > tray.reels[0].seen)
+        .collect();
+
+    let seen = client.mark_stories_as_seen(&unseen).await.unwrap();
+
+    assert_eq!(seen.item_ids, vec!["2300000000000000002_8999249"]);
+
+    m_tray.assert();
+    m_seen.assert();
+}
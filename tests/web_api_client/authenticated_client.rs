@@ -7,10 +7,10 @@ use instagram::web_api::behaviour::*;
 #[test]
 fn behaviour() {
     assert_impl!(FetchUserInfos: Client);
-    assert_impl!(!FetchUserFeed: Client);
+    assert_impl!(FetchUserFeed: Client);
+    assert_impl!(FetchMediaInfos: Client);
+    assert_impl!(FetchMediaComments: Client);
     // TODO implementation needed
-    assert_impl!(!FetchMediaInfos: Client);
-    assert_impl!(!FetchMediaComments: Client);
     assert_impl!(!Search: Client);
     assert_impl!(!FetchTagFeed: Client);
     assert_impl!(!FetchLocationFeed: Client);
@@ -24,14 +24,17 @@ fn behaviour() {
     assert_impl!(!FetchUserFollowers: Client);
     assert_impl!(!LikePost: Client);
     assert_impl!(!CancelPostLike: Client);
-    assert_impl!(!DeleteMedia: Client);
+    assert_impl!(DeleteMedia: Client);
+    assert_impl!(ArchiveMedia: Client);
+    assert_impl!(EditMediaCaption: Client);
     assert_impl!(!Follow: Client);
     assert_impl!(!UnFollow: Client);
     assert_impl!(!CommentPost: Client);
     assert_impl!(!DeleteComment: Client);
-    assert_impl!(!PostPhoto: Client);
-    assert_impl!(!FetchTimelineFeed: Client);
-    assert_impl!(!FetchReelsTray: Client);
-    assert_impl!(!FetchReelsFeed: Client);
-    assert_impl!(!FetchHighlightReels: Client);
+    assert_impl!(PostPhoto: Client);
+    assert_impl!(FetchTimelineFeed: Client);
+    assert_impl!(FetchReelsTray: Client);
+    assert_impl!(FetchReelsFeed: Client);
+    assert_impl!(MarkStoriesAsSeen: Client);
+    assert_impl!(FetchHighlightReels: Client);
 }
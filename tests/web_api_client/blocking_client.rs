@@ -0,0 +1,95 @@
+extern crate instagram;
+
+use mockito::mock;
+use mockito::Matcher;
+
+use instagram::web_api::blocking::behaviour::*;
+use instagram::web_api::blocking::{AuthenticatedClient, Client};
+use instagram::web_api::Credentials;
+
+fn blocking_client() -> Client {
+    let api_url = format!("{}/blocking", mockito::server_url());
+    let graphql_api_url = format!("{}/blocking/graphql", mockito::server_url());
+
+    Client::from(instagram::web_api::Client::new_with_url(
+        &api_url,
+        &graphql_api_url,
+    ))
+}
+
+#[test]
+fn test_blocking_fetch_user_infos_and_feed() {
+    let fixture_infos: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_user_info.json").unwrap();
+    let fixture_feed: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_user_feed.json").unwrap();
+
+    let m_user_info = mock("GET", "/blocking/freyskeyd")
+        .match_query(Matcher::UrlEncoded("__a".into(), "1".into()))
+        .with_status(200)
+        .with_body(&fixture_infos)
+        .expect(1)
+        .create();
+
+    let m_user_feed = mock("GET", "/blocking/graphql")
+        .match_query(Matcher::Regex("query_hash=.*variables=.*".into()))
+        .with_status(200)
+        .with_body(&fixture_feed)
+        .expect(1)
+        .create();
+
+    let client = blocking_client();
+
+    let user = client.fetch_user_infos("freyskeyd").unwrap();
+
+    assert_eq!(user.username, "freyskeyd");
+
+    let feed = client.fetch_user_feed(user.id, None).unwrap();
+
+    assert_eq!(feed.count, 147);
+    assert_eq!(feed.medias[0].shortcode().to_string(), "B8690canc8f");
+
+    m_user_info.assert();
+    m_user_feed.assert();
+}
+
+#[test]
+fn test_blocking_login() {
+    let fixture = "{\"authenticated\": true, \"user\": true, \"userId\": \"8343444274\", \"oneTapPrompt\": false, \"status\": \"ok\"}";
+
+    let fixture_init_rollout_hash: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_init_rollout.html").unwrap();
+
+    let m_root = mock("GET", "/blocking/")
+        .with_body(fixture_init_rollout_hash)
+        .with_status(200)
+        .expect(1)
+        .create();
+
+    let m_login = mock("POST", "/blocking/accounts/login/ajax/")
+        .with_status(200)
+        .with_header("set-cookie", "csrftoken=logged_in_token; Path=/")
+        .with_header("set-cookie", "sessionid=some_session; Path=/")
+        .with_body(fixture)
+        .expect(1)
+        .create();
+
+    let client = blocking_client()
+        .login(&Credentials {
+            username: "user",
+            password: "passw",
+        })
+        .unwrap();
+
+    let session = client.session();
+
+    assert_eq!(session.csrf_token.as_deref(), Some("logged_in_token"));
+    assert_eq!(session.cookie("sessionid"), Some("some_session"));
+
+    let resumed = AuthenticatedClient::from_session(blocking_client(), session.clone());
+
+    assert_eq!(resumed.session(), session);
+
+    m_root.assert();
+    m_login.assert();
+}
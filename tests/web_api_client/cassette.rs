@@ -0,0 +1,98 @@
+extern crate instagram;
+
+use mockito::mock;
+
+use instagram::web_api::behaviour::*;
+use instagram::web_api::cassette::{Cassette, RecordTransport, ReplayTransport, REDACTED};
+use instagram::web_api::transport::{Request, ReqwestTransport, Transport, TransportError};
+use instagram::web_api::Client;
+
+#[tokio::test]
+async fn test_record_and_replay() {
+    let path = std::env::temp_dir().join("instagram-cassette-test.json");
+
+    let m = mock("POST", "/cassette/login")
+        .with_status(200)
+        .with_header("set-cookie", "sessionid=secret; Path=/")
+        .with_body(r#"{"status": "ok", "config": {"csrf_token": "body-token"}, "echo": "secret"}"#)
+        .expect(1)
+        .create();
+
+    let request = Request::post(&format!("{}/cassette/login", mockito::server_url()))
+        .header("cookie", "sessionid=secret")
+        .header("x-csrftoken", "csrf-value")
+        .form(&[
+            ("username", "user"),
+            ("password", "secret"),
+            ("queryParams", "{}"),
+        ]);
+
+    let recorder = RecordTransport::new(ReqwestTransport::new(), &path);
+    let response = recorder.send(request.clone()).await.unwrap();
+
+    assert_eq!(
+        response.cookies(),
+        vec![("sessionid".into(), "secret".into())]
+    );
+    m.assert();
+
+    let cassette = Cassette::load(&path).unwrap();
+
+    assert_eq!(cassette, recorder.cassette());
+
+    let saved = std::fs::read_to_string(&path).unwrap();
+
+    assert!(!saved.contains("secret"));
+    assert!(!saved.contains("csrf-value"));
+    assert!(!saved.contains("body-token"));
+    assert!(saved.contains(&format!("sessionid={REDACTED}")));
+
+    let replayer = ReplayTransport::new(cassette);
+    let other_credentials = Request::post(&request.url).form(&[
+        ("username", "other"),
+        ("password", "other"),
+        ("queryParams", "{}"),
+    ]);
+    let replayed = replayer.send(other_credentials).await.unwrap();
+
+    assert_eq!(replayed.status, response.status);
+    assert_eq!(
+        String::from_utf8(replayed.body).unwrap(),
+        format!(
+            r#"{{"status": "ok", "config": {{"csrf_token": "{REDACTED}"}}, "echo": "{REDACTED}"}}"#
+        )
+    );
+    assert!(replayer.is_exhausted());
+
+    assert!(matches!(
+        replayer.send(request).await,
+        Err(TransportError::NotRecorded(_))
+    ));
+
+    std::fs::remove_file(&path).unwrap();
+}
+
+#[test]
+fn test_cassettes_hold_no_token() {
+    let page = std::fs::read_to_string("tests/web_api_client/response_init_rollout.html").unwrap();
+    let token = page
+        .split("\"csrf_token\":\"")
+        .nth(1)
+        .and_then(|rest| rest.split('"').next())
+        .unwrap();
+
+    for cassette in &["login", "login_2fa"] {
+        let path = format!("tests/web_api_client/cassettes/{cassette}.json");
+        let saved = std::fs::read_to_string(&path).unwrap();
+
+        assert!(!saved.contains(token), "{} holds the csrf token", path);
+    }
+}
+
+#[tokio::test]
+async fn test_replay_fails_on_unmatched_requests() {
+    let cassette = Cassette::load("tests/web_api_client/cassettes/login.json".as_ref()).unwrap();
+    let client = Client::new().with_transport(ReplayTransport::new(cassette));
+
+    assert!(client.fetch_user_infos("someone_else").await.is_err());
+}
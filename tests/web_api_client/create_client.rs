@@ -1,23 +1,27 @@
 extern crate instagram;
 
 lazy_static! {
-    static ref INSTAGRAM_USERNAME: String = {
-        std::env::var("INSTAGRAM_USERNAME")
-            .expect("You need to define INSTAGRAM_USERNAME environment variable.")
-    };
-    static ref INSTAGRAM_PASSWORD: String = {
-        std::env::var("INSTAGRAM_PASSWORD")
-            .expect("You need to define INSTAGRAM_PASSWORD environment variable.")
-    };
+    /// The cassettes are recorded against Instagram when it's defined
+    static ref INSTAGRAM_RECORD: bool = std::env::var("INSTAGRAM_RECORD").is_ok();
+    static ref INSTAGRAM_USERNAME: String = std::env::var("INSTAGRAM_USERNAME")
+        .unwrap_or_else(|_| recording_var("INSTAGRAM_USERNAME"));
+    static ref INSTAGRAM_PASSWORD: String = std::env::var("INSTAGRAM_PASSWORD")
+        .unwrap_or_else(|_| recording_var("INSTAGRAM_PASSWORD"));
 }
 
 use mockito::mock;
 use mockito::Matcher;
 
 use instagram::web_api::behaviour::*;
+use instagram::web_api::cassette::{RecordTransport, ReplayTransport};
+use instagram::web_api::domain::{EntityKind, MediaId, MediaKind};
+use instagram::web_api::schema::SchemaDrift;
+use instagram::web_api::transport::ReqwestTransport;
 use instagram::web_api::Client;
 use instagram::web_api::ClientError;
 use instagram::web_api::Credentials;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
 
 #[tokio::test]
 async fn test_get_user_info() {
@@ -38,6 +42,20 @@ async fn test_get_user_info() {
 
     assert_eq!(freyskeyd_infos.username, "freyskeyd");
     assert_eq!(freyskeyd_infos.full_name, "FREYSKEYD");
+    assert_eq!(
+        freyskeyd_infos.url(),
+        "https://www.instagram.com/freyskeyd/"
+    );
+    assert_eq!(freyskeyd_infos.follower_count, 2810);
+    assert_eq!(freyskeyd_infos.following_count, 652);
+    assert_eq!(freyskeyd_infos.post_count(), Some(143));
+    assert!(freyskeyd_infos.mutual_followers.usernames.is_empty());
+    assert!(freyskeyd_infos.bio_links.is_empty());
+
+    let timeline_media = freyskeyd_infos.timeline_media.as_ref().unwrap();
+
+    assert_eq!(timeline_media.medias.len(), 12);
+    assert_eq!(timeline_media.medias[0].comments().count(), 4);
 
     let m = mock("GET", "/Freyskeyd")
         .match_query(Matcher::UrlEncoded("__a".into(), "1".into()))
@@ -53,101 +71,446 @@ async fn test_get_user_info() {
 }
 
 #[tokio::test]
-async fn test_logged_in_2_fa() {
+async fn test_get_business_user_info() {
     let fixture: String =
-        ::std::fs::read_to_string("tests/web_api_client/response_login_2FA.json").unwrap();
-
-    let fixture_init_rollout_hash: String =
-        ::std::fs::read_to_string("tests/web_api_client/response_init_rollout.html").unwrap();
+        ::std::fs::read_to_string("tests/web_api_client/response_user_info_business.json").unwrap();
 
-    let m_root = mock("GET", "/")
-        .with_body(fixture_init_rollout_hash)
+    let m = mock("GET", "/freyskeyd")
+        .match_query(Matcher::UrlEncoded("__a".into(), "1".into()))
         .with_status(200)
+        .with_body(&fixture)
         .expect(1)
         .create();
 
-    let m_login = mock("POST", "/accounts/login/ajax/")
-        .with_status(400)
+    let client = Client::new_with_url(&mockito::server_url(), "");
+    let freyskeyd_infos = client.fetch_user_infos("freyskeyd").await.unwrap();
+
+    m.assert();
+
+    assert_eq!(
+        freyskeyd_infos.mutual_followers.usernames,
+        vec!["linformelle"]
+    );
+    assert_eq!(freyskeyd_infos.bio_links[0].url, "https://freyskeyd.fr/");
+    assert_eq!(freyskeyd_infos.pronouns, vec!["he", "him"]);
+    assert_eq!(
+        freyskeyd_infos.category_enum.as_deref(),
+        Some("PHOTOGRAPHER")
+    );
+
+    let contact = &freyskeyd_infos.business_contact;
+
+    assert_eq!(
+        contact.business_email.as_deref(),
+        Some("contact@freyskeyd.fr")
+    );
+    assert_eq!(
+        contact
+            .business_address
+            .as_ref()
+            .unwrap()
+            .zip_code
+            .as_deref(),
+        Some("44000")
+    );
+}
+
+#[tokio::test]
+async fn test_strict_mode_reports_drifts() {
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_user_info.json").unwrap();
+
+    let m = mock("GET", "/freyskeyd")
+        .match_query(Matcher::UrlEncoded("__a".into(), "1".into()))
+        .with_status(200)
         .with_body(&fixture)
         .expect(1)
         .create();
 
-    let x = Client::new_with_url(&mockito::server_url(), "")
-        .login(&get_credentials())
-        .await;
+    let drifts = Arc::new(Mutex::new(Vec::new()));
+    let reported = Arc::clone(&drifts);
 
-    assert_eq!(Err(ClientError::UnableToPerform2FA), x);
+    let client = Client::new_with_url(&mockito::server_url(), "")
+        .strict(move |drift| reported.lock().unwrap().push(drift.clone()));
+
+    assert!(client.fetch_user_infos("freyskeyd").await.is_ok());
+
+    let drifts = drifts.lock().unwrap();
+
+    assert!(drifts.contains(&SchemaDrift::UnknownField("logging_page_id".into())));
+    assert!(drifts.contains(&SchemaDrift::UnknownField(
+        "graphql.user.edge_owner_to_timeline_media.edges[0].node.accessibility_caption".into()
+    )));
+    assert!(drifts.contains(&SchemaDrift::UnknownField(
+        "graphql.user.edge_saved_media".into()
+    )));
+    assert!(drifts.contains(&SchemaDrift::MissingField("graphql.user.bio_links".into())));
+    assert!(drifts.contains(&SchemaDrift::MissingField(
+        "graphql.user.edge_owner_to_timeline_media.edges[0].node.product_type".into()
+    )));
+    assert!(!drifts
+        .iter()
+        .any(|drift| drift.path().ends_with("edge_media_preview_comment")));
+
+    m.assert();
+}
 
-    m_root.assert();
-    m_login.assert();
+#[tokio::test]
+async fn test_logged_in_2_fa() {
+    let x = cassette_client("login_2fa").login(&get_credentials()).await;
+
+    assert_eq!(Err(ClientError::UnableToPerform2FA), x);
 }
 
 #[tokio::test]
 async fn test_logged_in() {
-    let fixture = "{\"authenticated\": true, \"user\": true, \"userId\": \"8343444274\", \"oneTapPrompt\": false, \"status\": \"ok\"}";
+    let client = cassette_client("login")
+        .login(&get_credentials())
+        .await
+        .unwrap();
+    let freyskeyd_infos = client.fetch_user_infos("Freyskeyd").await.unwrap();
 
-    let fixture_init_rollout_hash: String =
-        ::std::fs::read_to_string("tests/web_api_client/response_init_rollout.html").unwrap();
+    assert_eq!(freyskeyd_infos.username, "freyskeyd");
+    assert_eq!(freyskeyd_infos.full_name, "FREYSKEYD");
+}
 
-    let fixture_infos: String =
-        ::std::fs::read_to_string("tests/web_api_client/response_user_info.json").unwrap();
+#[tokio::test]
+async fn test_user_feed() {
+    let _ = env_logger::try_init();
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_user_feed.json").unwrap();
 
-    let m_root = mock("GET", "/")
-        .with_body(fixture_init_rollout_hash)
+    let m_user_feed = mock("GET", "/")
+        .match_query(Matcher::Regex("query_hash=.*variables=.*".into()))
         .with_status(200)
+        .with_body(&fixture)
         .expect(1)
         .create();
 
-    let m_login = mock("POST", "/accounts/login/ajax/")
+    let user_feed = Client::new_with_url("", &mockito::server_url())
+        .fetch_user_feed("1234".parse().unwrap(), None)
+        .await
+        .unwrap();
+
+    assert_eq!(user_feed.count, 147);
+    assert_eq!(
+        user_feed.medias[0].url(),
+        "https://www.instagram.com/p/B8690canc8f/"
+    );
+
+    let media = &user_feed.medias[0];
+
+    assert_eq!(media.like_count(), 62);
+    assert_eq!(media.comments().count(), 2);
+    assert_eq!(media.owner().username(), "freyskeyd");
+    assert!(media
+        .caption()
+        .unwrap()
+        .starts_with("Nouveau triptyque portrait"));
+
+    let serialized = serde_json::to_value(media).unwrap();
+
+    assert_eq!(serialized["shortcode"], "B8690canc8f");
+    assert_eq!(serialized["owner"]["id"], "8999249");
+    assert_eq!(serialized["like_count"], 62);
+    assert_eq!(
+        media.hashtags(),
+        vec!["bnwportrait", "freelensingphotographer"]
+    );
+    assert_eq!(media.mentions(), vec!["linformelle"]);
+    assert_eq!(media.caption_entities()[1].utf16_range(), 50..62);
+    assert_eq!(
+        media.comments().comments()[1].entities()[0].value(),
+        "visual_ade_photography"
+    );
+    assert_eq!(
+        user_feed.medias[7].comments().comments()[0].entities()[0].kind(),
+        EntityKind::Emoji
+    );
+    assert_eq!(media.kind(), MediaKind::Image);
+
+    assert_eq!(media.location().unwrap().name(), "Nantes, France");
+    assert!(user_feed.medias[1].location().is_none());
+    assert!(!media.is_paid_partnership());
+    assert!(media.gating().is_none());
+
+    let tag = &user_feed.medias[6].user_tags()[0];
+
+    assert_eq!(tag.user().username(), "effysmile.stone");
+    assert!((tag.x() - 0.601_333_333_3).abs() < f64::EPSILON);
+    assert!((tag.y() - 0.586_666_666_7).abs() < f64::EPSILON);
+
+    m_user_feed.assert();
+}
+
+#[tokio::test]
+async fn test_user_feed_carousel_and_reel() {
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_user_feed_carousel_reel.json")
+            .unwrap();
+
+    let m_user_feed = mock("GET", "/")
+        .match_query(Matcher::Regex("query_hash=.*variables=.*".into()))
         .with_status(200)
-        .with_body(fixture)
+        .with_body(&fixture)
         .expect(1)
         .create();
 
-    let m_user_info = mock("GET", "/Freyskeyd")
+    let user_feed = Client::new_with_url("", &mockito::server_url())
+        .fetch_user_feed("8999249".parse().unwrap(), None)
+        .await
+        .unwrap();
+
+    let carousel = &user_feed.medias[0];
+
+    assert_eq!(carousel.kind(), MediaKind::Carousel);
+    assert_eq!(carousel.children().len(), 2);
+    assert_eq!(carousel.children()[0].kind(), MediaKind::Image);
+    assert_eq!(carousel.children()[0].display_resources().len(), 3);
+    assert_eq!(carousel.children()[1].kind(), MediaKind::Video);
+    assert!(carousel.children()[1]
+        .video_url()
+        .unwrap()
+        .ends_with("85000003_n.mp4"));
+
+    let reel = &user_feed.medias[1];
+
+    assert_eq!(reel.kind(), MediaKind::Reel);
+    assert!(reel.children().is_empty());
+    assert_eq!(reel.video_view_count(), Some(20480));
+
+    assert_eq!(
+        carousel.children()[1].user_tags()[0].user().username(),
+        "freyskeyd"
+    );
+    assert_eq!(
+        carousel.gating().unwrap().gating_type(),
+        Some("sensitivity")
+    );
+
+    assert!(reel.is_paid_partnership());
+    assert_eq!(reel.sponsors()[0].username(), "ilfordphoto");
+    assert_eq!(reel.fact_check_overall_rating(), Some("false"));
+    assert_eq!(
+        reel.fact_check().unwrap().title(),
+        Some("False Information")
+    );
+    assert_eq!(reel.overlay().unwrap().overlay_type(), Some("fact_check"));
+
+    m_user_feed.assert();
+}
+
+#[tokio::test]
+async fn test_media_infos() {
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_media_infos.json").unwrap();
+
+    let media_id: MediaId = "2245000000000000001".parse().unwrap();
+
+    let m_media = mock("GET", format!("/p/{}/", media_id.to_shortcode()).as_str())
         .match_query(Matcher::UrlEncoded("__a".into(), "1".into()))
         .with_status(200)
-        .with_body(&fixture_infos)
+        .with_body(&fixture)
         .expect(1)
         .create();
 
-    let client = Client::new_with_url(&mockito::server_url(), "")
-        .login(&get_credentials())
+    let media = Client::new_with_url(&mockito::server_url(), "")
+        .fetch_media_infos(media_id)
         .await
         .unwrap();
-    let freyskeyd_infos = client.fetch_user_infos("Freyskeyd").await.unwrap();
 
-    assert_eq!(freyskeyd_infos.username, "freyskeyd");
-    assert_eq!(freyskeyd_infos.full_name, "FREYSKEYD");
+    assert_eq!(media.id(), media_id);
+    assert_eq!(media.kind(), MediaKind::Carousel);
+    assert_eq!(media.children().len(), 2);
+    assert_eq!(media.comments().comments().len(), 2);
 
-    m_root.assert();
-    m_login.assert();
-    m_user_info.assert();
+    m_media.assert();
 }
 
 #[tokio::test]
-async fn test_user_feed() {
-    let _ = env_logger::try_init();
+async fn test_decode_error() {
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_media_infos.json").unwrap();
+    let mut fixture: serde_json::Value = serde_json::from_str(&fixture).unwrap();
+
+    fixture["graphql"]["shortcode_media"]["comments_disabled"] = "no".into();
+
+    let media_id: MediaId = "2245000000000000002".parse().unwrap();
+
+    let m_media = mock("GET", format!("/p/{}/", media_id.to_shortcode()).as_str())
+        .match_query(Matcher::UrlEncoded("__a".into(), "1".into()))
+        .with_status(200)
+        .with_body(fixture.to_string())
+        .expect(1)
+        .create();
+
+    let error = Client::new_with_url(&mockito::server_url(), "")
+        .fetch_media_infos(media_id)
+        .await
+        .unwrap_err();
+
+    match error {
+        ClientError::Decode(error) => {
+            assert_eq!(error.path(), "graphql.shortcode_media.comments_disabled");
+            assert!(error.message().contains("expected a boolean"));
+        }
+        other => panic!("expected a decode error, got {:?}", other),
+    }
+
+    m_media.assert();
+}
+
+#[tokio::test]
+async fn test_lenient_user_feed() {
     let fixture: String =
         ::std::fs::read_to_string("tests/web_api_client/response_user_feed.json").unwrap();
+    let mut fixture: serde_json::Value = serde_json::from_str(&fixture).unwrap();
+
+    let edges = fixture
+        .pointer_mut("/data/user/edge_owner_to_timeline_media/edges")
+        .unwrap();
+
+    edges[0]["node"]["edge_media_to_comment"]["edges"][1]["node"]
+        .as_object_mut()
+        .unwrap()
+        .remove("text");
+    edges[2]["node"]["shortcode"] = "not a shortcode!".into();
+
+    let fixture = fixture.to_string();
 
     let m_user_feed = mock("GET", "/")
         .match_query(Matcher::Regex("query_hash=.*variables=.*".into()))
         .with_status(200)
         .with_body(&fixture)
-        .expect(1)
+        .expect(3)
         .create();
 
-    let user_feed = Client::new_with_url("", &mockito::server_url())
-        .fetch_user_feed("1234", None)
+    let client = Client::new_with_url("", &mockito::server_url());
+
+    assert!(client
+        .fetch_user_feed("1234".parse().unwrap(), None)
+        .await
+        .is_err());
+
+    let user_feed = client
+        .clone()
+        .lenient()
+        .fetch_user_feed("1234".parse().unwrap(), None)
         .await
         .unwrap();
 
     assert_eq!(user_feed.count, 147);
+    assert_eq!(user_feed.medias.len(), 11);
+    assert_eq!(user_feed.medias[0].comments().comments().len(), 1);
+    assert!(user_feed.pagination_infos.has_next_page());
+
+    let paths: Vec<&str> = user_feed
+        .errors
+        .iter()
+        .map(|error| error.path.as_str())
+        .collect();
+
+    assert_eq!(
+        paths,
+        vec![
+            "data.user.edge_owner_to_timeline_media.edges[0].node.edge_media_to_comment.edges[1].node",
+            "data.user.edge_owner_to_timeline_media.edges[2].node",
+        ]
+    );
+
+    let drifts = Arc::new(Mutex::new(Vec::new()));
+    let reported = Arc::clone(&drifts);
+
+    client
+        .lenient()
+        .strict(move |drift| reported.lock().unwrap().push(drift.clone()))
+        .fetch_user_feed("1234".parse().unwrap(), None)
+        .await
+        .unwrap();
+
+    let drifts = drifts.lock().unwrap();
+
+    assert!(drifts.contains(&SchemaDrift::UnknownField(
+        "data.user.edge_owner_to_timeline_media.edges[1].node.accessibility_caption".into()
+    )));
+    assert!(drifts.contains(&SchemaDrift::MissingField(
+        "data.user.edge_owner_to_timeline_media.edges[1].node.product_type".into()
+    )));
 
     m_user_feed.assert();
 }
 
+#[tokio::test]
+async fn test_lenient_media_comments() {
+    let fixture: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_media_comments.json").unwrap();
+    let mut fixture: serde_json::Value = serde_json::from_str(&fixture).unwrap();
+
+    fixture
+        .pointer_mut("/data/shortcode_media/edge_media_to_parent_comment/edges/0/node")
+        .unwrap()
+        .as_object_mut()
+        .unwrap()
+        .remove("text");
+
+    let m_comments = mock("GET", "/")
+        .match_query(Matcher::UrlEncoded(
+            "query_hash".into(),
+            "bc3296d1ce80a24b1b6e40b1e72903f5".into(),
+        ))
+        .with_status(200)
+        .with_body(fixture.to_string())
+        .expect(2)
+        .create();
+
+    let client = Client::new_with_url("", &mockito::server_url());
+    let media_id: MediaId = "2250382848586272543".parse().unwrap();
+
+    assert!(client
+        .clone()
+        .fetch_media_comments(media_id, None)
+        .await
+        .is_err());
+
+    let comments = client
+        .lenient()
+        .fetch_media_comments(media_id, None)
+        .await
+        .unwrap();
+
+    assert_eq!(comments.count(), 2);
+    assert!(comments.comments().is_empty());
+    assert!(comments.pagination_infos().has_next_page());
+    assert_eq!(
+        comments.errors()[0].path,
+        "data.shortcode_media.edge_media_to_parent_comment.edges[0].node"
+    );
+
+    m_comments.assert();
+}
+
+/// Credentials are only needed to record, the replayed requests have them redacted
+fn recording_var(name: &str) -> String {
+    assert!(
+        !*INSTAGRAM_RECORD,
+        "You need to define {} environment variable to record the cassettes.",
+        name
+    );
+
+    "user".to_string()
+}
+
+/// Client replaying `tests/web_api_client/cassettes/{name}.json`, or recording it
+fn cassette_client(name: &str) -> Client {
+    let path = PathBuf::from(format!("tests/web_api_client/cassettes/{name}.json"));
+
+    if *INSTAGRAM_RECORD {
+        Client::new().with_transport(RecordTransport::new(ReqwestTransport::new(), &path))
+    } else {
+        Client::new().with_transport(ReplayTransport::load(&path).unwrap())
+    }
+}
+
 fn get_credentials() -> Credentials<'static> {
     Credentials {
         username: &INSTAGRAM_USERNAME,
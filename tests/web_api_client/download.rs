@@ -0,0 +1,197 @@
+extern crate instagram;
+
+use async_trait::async_trait;
+use mockito::mock;
+use mockito::Matcher;
+use std::sync::{Arc, Mutex};
+
+use instagram::web_api::domain::{Media, MediaKind, Reel};
+use instagram::web_api::download::{Downloadable, Downloader, ResourceKind, Size};
+use instagram::web_api::transport::{
+    HeaderMap, HeaderValue, Request, Response, StatusCode, Transport, TransportError,
+};
+use instagram::web_api::{Client, ClientError};
+
+/// Transport serving the files of a CDN from memory, keeping the urls it received
+#[derive(Clone, Default)]
+struct InMemoryCdn {
+    urls: Arc<Mutex<Vec<String>>>,
+}
+
+#[async_trait]
+impl Transport for InMemoryCdn {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        self.urls.lock().unwrap().push(request.url.clone());
+
+        let mut headers = HeaderMap::new();
+        let (status, body) = if request.url.contains("missing") {
+            (StatusCode::NOT_FOUND, Vec::new())
+        } else {
+            headers.insert("content-type", HeaderValue::from_static("image/jpeg"));
+
+            (StatusCode::OK, b"jpeg bytes".to_vec())
+        };
+
+        Ok(Response {
+            status,
+            headers,
+            body,
+        })
+    }
+}
+
+fn media_fixture(file: &str, pointer: &str, cdn: &str) -> serde_json::Value {
+    let fixture: String = ::std::fs::read_to_string(file)
+        .unwrap()
+        .replace(cdn, &mockito::server_url());
+    let mut fixture: serde_json::Value = serde_json::from_str(&fixture).unwrap();
+
+    fixture.pointer_mut(pointer).unwrap().take()
+}
+
+#[tokio::test]
+async fn test_download_carousel_to_dir() {
+    let carousel: Media = serde_json::from_value(media_fixture(
+        "tests/web_api_client/response_user_feed_carousel_reel.json",
+        "/data/user/edge_owner_to_timeline_media/edges/0/node",
+        "https://scontent-cdg2-1.cdninstagram.com",
+    ))
+    .unwrap();
+
+    assert_eq!(carousel.kind(), MediaKind::Carousel);
+
+    let items = carousel.download_items();
+
+    assert_eq!(items.len(), 2);
+    assert_eq!(items[0].resources.len(), 4);
+    assert_eq!(
+        items[0].select(Size::AtLeast(700), true).unwrap().width,
+        750
+    );
+    assert_eq!(
+        items[1].select(Size::Best, true).unwrap().kind,
+        ResourceKind::Video
+    );
+    assert_eq!(
+        items[1].select(Size::Best, false).unwrap().kind,
+        ResourceKind::Image
+    );
+
+    let m_images = mock("GET", Matcher::Regex("^/v/t51".into()))
+        .with_status(200)
+        .with_header("content-type", "image/jpeg")
+        .with_body("jpeg bytes")
+        .expect(1)
+        .create();
+
+    let m_videos = mock("GET", Matcher::Regex("^/v/t50".into()))
+        .with_status(200)
+        .with_header("content-type", "video/mp4")
+        .with_body("mp4 bytes")
+        .expect(1)
+        .create();
+
+    let dir = std::env::temp_dir().join("instagram-test-download-carousel");
+
+    let downloads = Downloader::new()
+        .set_size(Size::Smallest)
+        .download_to_dir(&carousel, &dir)
+        .await
+        .unwrap();
+
+    assert_eq!(downloads.len(), 2);
+    assert!(downloads[0].url.ends_with("/s640x640/85000002_n.jpg"));
+    assert_eq!(downloads[0].extension, "jpg");
+    assert_eq!(downloads[0].length, 10);
+    assert_eq!(
+        downloads[0].sha256,
+        "1b48e21282963dfba2ffff3a4c331471242fe42fd0a51161e56df72085c445c9"
+    );
+    assert_eq!(
+        downloads[0].path.as_deref(),
+        Some(dir.join("2245000000000000002.jpg").as_path())
+    );
+    assert_eq!(downloads[1].content_type.as_deref(), Some("video/mp4"));
+    assert_eq!(
+        std::fs::read_to_string(dir.join("2245000000000000003.mp4")).unwrap(),
+        "mp4 bytes"
+    );
+
+    std::fs::remove_dir_all(&dir).unwrap();
+
+    m_images.assert();
+    m_videos.assert();
+}
+
+#[tokio::test]
+async fn test_download_story_to_writer() {
+    let reel: Reel = serde_json::from_value(media_fixture(
+        "tests/web_api_client/response_reels_media.json",
+        "/reels/8999249",
+        "https://scontent.cdninstagram.com",
+    ))
+    .unwrap();
+
+    let m_story = mock("GET", "/2300000000000000001_1080.jpg")
+        .with_status(200)
+        .with_body("jpeg bytes")
+        .expect(1)
+        .create();
+
+    let items = reel.items[0].download_items();
+    let resource = items[0].select(Size::Best, true).unwrap();
+
+    assert_eq!(resource.width, 1080);
+
+    let mut buffer = Vec::new();
+    let download = Downloader::new()
+        .download(resource, &mut buffer)
+        .await
+        .unwrap();
+
+    assert_eq!(buffer, b"jpeg bytes");
+    assert_eq!(download.extension, "jpg");
+    assert!(download.path.is_none());
+
+    m_story.assert();
+}
+
+#[tokio::test]
+async fn test_download_through_transport() {
+    let reel: Reel = serde_json::from_value(media_fixture(
+        "tests/web_api_client/response_reels_media.json",
+        "/reels/8999249",
+        "https://scontent.cdninstagram.com",
+    ))
+    .unwrap();
+
+    let cdn = InMemoryCdn::default();
+    let client = Client::new().with_transport(cdn.clone());
+
+    let items = reel.items[0].download_items();
+    let resource = items[0].select(Size::Best, true).unwrap();
+
+    let mut buffer = Vec::new();
+    let download = client
+        .downloader()
+        .download(resource, &mut buffer)
+        .await
+        .unwrap();
+
+    assert_eq!(buffer, b"jpeg bytes");
+    assert_eq!(download.url, resource.url);
+    assert_eq!(download.content_type.as_deref(), Some("image/jpeg"));
+    assert_eq!(*cdn.urls.lock().unwrap(), vec![resource.url.to_string()]);
+
+    let mut missing = *resource;
+    missing.url = "https://scontent.cdninstagram.com/missing.jpg";
+
+    assert_eq!(
+        Downloader::new()
+            .with_transport(cdn.clone())
+            .download(&missing, &mut Vec::new())
+            .await,
+        Err(ClientError::HttpRequest)
+    );
+    assert_eq!(cdn.urls.lock().unwrap().len(), 2);
+}
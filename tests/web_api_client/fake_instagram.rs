@@ -0,0 +1,274 @@
+extern crate instagram;
+
+use instagram::web_api::behaviour::*;
+use instagram::web_api::domain::{MediaId, MediaKind, TimelineItem, UserId};
+use instagram::web_api::options::{
+    FetchMediaCommentsOptions, FetchUserFeedOptions, Photo, PostPhotoOptions, UserTag,
+};
+use instagram::web_api::response::UserInfosError;
+use instagram::web_api::testing::FakeInstagram;
+use instagram::web_api::ClientError;
+
+#[test]
+fn behaviour() {
+    assert_impl!(FetchUserInfos: FakeInstagram);
+    assert_impl!(FetchUserFeed: FakeInstagram);
+    assert_impl!(FetchMediaInfos: FakeInstagram);
+    assert_impl!(FetchMediaComments: FakeInstagram);
+    assert_impl!(DeleteMedia: FakeInstagram);
+    assert_impl!(ArchiveMedia: FakeInstagram);
+    assert_impl!(EditMediaCaption: FakeInstagram);
+    assert_impl!(PostPhoto: FakeInstagram);
+    assert_impl!(FetchTimelineFeed: FakeInstagram);
+    assert_impl!(FetchReelsTray: FakeInstagram);
+    assert_impl!(FetchReelsFeed: FakeInstagram);
+    assert_impl!(FetchHighlightReels: FakeInstagram);
+    assert_impl!(MarkStoriesAsSeen: FakeInstagram);
+}
+
+#[tokio::test]
+async fn test_fake_profiles_and_feeds() {
+    let instagram = FakeInstagram::new();
+
+    let alice = instagram.add_user("alice");
+    let bob = instagram.add_user("bob");
+
+    instagram.follow(bob, alice);
+
+    let first = instagram.add_post(alice, "First post #hello");
+    let _second = instagram.add_post(alice, "Second post");
+    let third = instagram.add_post(alice, "Third post @bob");
+
+    let _comment = instagram.add_comment(first, bob, "Nice one");
+    let _reply = instagram.add_comment(first, alice, "@bob thanks");
+    instagram.like(first);
+
+    let profile = instagram.fetch_user_infos("alice").await.unwrap();
+
+    assert_eq!(profile.id, alice);
+    assert_eq!(profile.follower_count, 1);
+    assert_eq!(profile.post_count(), Some(3));
+    assert!(!profile.followed_by_viewer);
+
+    assert!(matches!(
+        instagram.fetch_user_infos("carol").await,
+        Err(UserInfosError::NotFound)
+    ));
+    assert_eq!(
+        instagram
+            .fetch_user_feed(UserId::new(1), None)
+            .await
+            .unwrap_err(),
+        ClientError::NotFound
+    );
+    assert_eq!(
+        instagram
+            .fetch_media_infos(MediaId::new(1))
+            .await
+            .unwrap_err(),
+        ClientError::NotFound
+    );
+
+    let page = instagram
+        .fetch_user_feed(alice, Some(FetchUserFeedOptions::default().set_count(2)))
+        .await
+        .unwrap();
+
+    assert_eq!(page.count, 3);
+    assert_eq!(page.medias.len(), 2);
+    assert_eq!(page.medias[0].id(), third);
+    assert_eq!(page.medias[0].mentions(), vec!["bob"]);
+    assert!(page.pagination_infos.has_next_page());
+
+    let cursor = page.pagination_infos.end_cursor().unwrap().to_string();
+    let last_page = instagram
+        .fetch_user_feed(
+            alice,
+            Some(FetchUserFeedOptions::default().set_after(&cursor)),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(last_page.medias.len(), 1);
+    assert!(!last_page.pagination_infos.has_next_page());
+
+    let media = instagram.fetch_media_infos(first).await.unwrap();
+
+    assert_eq!(media.kind(), MediaKind::Image);
+    assert_eq!(media.shortcode(), &first.to_shortcode());
+    assert_eq!(media.owner().username(), "alice");
+    assert_eq!(media.like_count(), 1);
+    assert_eq!(media.comments().comments()[0].text(), "Nice one");
+    assert_eq!(media.comments().comments()[0].owner().username(), "bob");
+
+    let comments = instagram
+        .fetch_media_comments(
+            first,
+            Some(FetchMediaCommentsOptions::default().set_count(1)),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(comments.count(), 2);
+    assert_eq!(comments.comments().len(), 1);
+
+    let cursor = comments
+        .pagination_infos()
+        .end_cursor()
+        .unwrap()
+        .to_string();
+    let last_comments = instagram
+        .fetch_media_comments(
+            first,
+            Some(FetchMediaCommentsOptions::default().set_after(&cursor)),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(last_comments.comments()[0].text(), "@bob thanks");
+    assert!(!last_comments.pagination_infos().has_next_page());
+
+    instagram.update_user(alice, |user| user.is_private = true);
+
+    let hidden = instagram.fetch_user_feed(alice, None).await.unwrap();
+
+    assert_eq!(hidden.count, 3);
+    assert!(hidden.medias.is_empty());
+
+    instagram.log_in(bob);
+
+    let followed = instagram.fetch_user_feed(alice, None).await.unwrap();
+
+    assert_eq!(followed.medias.len(), 3);
+}
+
+#[tokio::test]
+async fn test_fake_actions() {
+    let instagram = FakeInstagram::new();
+
+    let alice = instagram.add_user("alice");
+    let bob = instagram.add_user("bob");
+    let post = instagram.add_post(alice, "Draft");
+
+    assert_eq!(
+        instagram.archive_media(post).await,
+        Err(ClientError::ActionFailed("login_required".to_string()))
+    );
+
+    instagram.log_in(bob);
+
+    assert!(instagram.delete_media(post).await.is_err());
+
+    instagram.log_in(alice);
+
+    let edition = instagram
+        .edit_media_caption(post, "Final #caption")
+        .await
+        .unwrap();
+
+    assert_eq!(edition.caption.as_deref(), Some("Final #caption"));
+    assert!(instagram.archive_media(post).await.unwrap().archived);
+    assert!(instagram.post(post).unwrap().archived);
+
+    instagram.log_in(bob);
+
+    assert_eq!(
+        instagram.fetch_media_infos(post).await.unwrap_err(),
+        ClientError::NotFound
+    );
+
+    instagram.log_in(alice);
+    instagram.unarchive_media(post).await.unwrap();
+
+    let jpeg = vec![
+        0xFF, 0xD8, 0xFF, 0xC0, 0x00, 0x0B, 0x08, 0x04, 0x38, 0x04, 0x38, 0x01, 0x01, 0x11, 0x00,
+    ];
+    let options = PostPhotoOptions::default()
+        .set_caption("Hello @bob")
+        .add_user_tag(UserTag {
+            user_id: bob,
+            x: 0.5,
+            y: 0.5,
+        });
+
+    let photo = instagram
+        .post_photo(Photo::Jpeg(jpeg), Some(options))
+        .await
+        .unwrap();
+
+    assert_eq!(photo.caption(), Some("Hello @bob"));
+    assert_eq!(photo.dimensions().width(), 1080);
+    assert_eq!(photo.user_tags()[0].user().username(), "bob");
+    assert_eq!(
+        instagram
+            .post_photo(Photo::Jpeg(b"\x89PNG".to_vec()), None)
+            .await
+            .unwrap_err(),
+        ClientError::InvalidPhoto
+    );
+
+    assert!(instagram.delete_media(post).await.unwrap().did_delete);
+    assert!(instagram.post(post).is_none());
+
+    let feed = instagram.fetch_user_feed(alice, None).await.unwrap();
+
+    assert_eq!(feed.count, 1);
+    assert_eq!(feed.medias[0].id(), photo.id());
+}
+
+#[tokio::test]
+async fn test_fake_timeline_and_stories() {
+    let instagram = FakeInstagram::new();
+
+    let alice = instagram.add_user("alice");
+    let bob = instagram.add_user("bob");
+    let carol = instagram.add_user("carol");
+
+    instagram.follow(alice, bob);
+    let _from_bob = instagram.add_post(bob, "From bob");
+    let _from_carol = instagram.add_post(carol, "From carol");
+    let _from_alice = instagram.add_post(alice, "From alice");
+
+    let story = instagram.add_story(bob);
+    let highlight = instagram.add_highlight(bob, "Holidays", &[story]);
+
+    assert!(instagram.fetch_timeline_feed(None).await.is_err());
+
+    instagram.log_in(alice);
+
+    let timeline = instagram.fetch_timeline_feed(None).await.unwrap();
+
+    assert_eq!(timeline.items.len(), 2);
+    assert!(matches!(
+        &timeline.items[0],
+        TimelineItem::Media(media) if media.owner().username() == "alice"
+    ));
+
+    let tray = instagram.fetch_reels_tray().await.unwrap();
+
+    assert_eq!(tray.reels.len(), 1);
+    assert_eq!(tray.unseen().count(), 1);
+
+    let reels = instagram.fetch_reels_feed(&[bob, carol]).await.unwrap();
+
+    assert_eq!(reels.len(), 1);
+    assert_eq!(reels[0].items[0].pk, story);
+    assert_eq!(reels[0].items[0].owner_id(), Some(bob));
+
+    let seen = instagram
+        .mark_stories_as_seen(&[&reels[0].items[0]])
+        .await
+        .unwrap();
+
+    assert_eq!(seen.item_ids, vec![reels[0].items[0].id.clone()]);
+    assert_eq!(
+        instagram.fetch_reels_tray().await.unwrap().unseen().count(),
+        0
+    );
+
+    let highlights = instagram.fetch_highlight_reels(bob).await.unwrap();
+
+    assert_eq!(highlights[0].id, highlight);
+    assert_eq!(highlights[0].title, "Holidays");
+    assert_eq!(highlights[0].items[0].pk, story);
+}
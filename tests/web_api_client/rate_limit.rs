@@ -0,0 +1,132 @@
+extern crate instagram;
+
+use async_trait::async_trait;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use instagram::web_api::behaviour::*;
+use instagram::web_api::rate_limit::{Budget, RateLimits};
+use instagram::web_api::retry::RetryPolicy;
+use instagram::web_api::transport::{
+    HeaderMap, Request, Response, StatusCode, Transport, TransportError,
+};
+use instagram::web_api::{Client, ClientBuilder};
+
+struct Fixtures;
+
+#[async_trait]
+impl Transport for Fixtures {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        let fixture = if request.url.contains("query_hash") {
+            "tests/web_api_client/response_user_feed.json"
+        } else {
+            "tests/web_api_client/response_user_info.json"
+        };
+
+        Ok(Response {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: std::fs::read(fixture).unwrap(),
+        })
+    }
+}
+
+/// Two requests at once, then one every 100ms
+fn client() -> Client {
+    let budget = Budget::new(2, Duration::from_millis(200));
+
+    ClientBuilder::new()
+        .set_rate_limits(RateLimits::new().graphql(budget).profile(budget))
+        .build()
+        .with_transport(Fixtures)
+}
+
+#[tokio::test]
+async fn test_budget_is_shared_across_clones() {
+    let client = client();
+    let clone = client.clone();
+
+    let start = Instant::now();
+
+    let (first, second) = tokio::join!(
+        client.fetch_user_infos("freyskeyd"),
+        clone.fetch_user_infos("freyskeyd")
+    );
+
+    assert!(first.is_ok() && second.is_ok());
+    assert!(start.elapsed() < Duration::from_millis(100));
+
+    let (first, second) = tokio::join!(
+        client.fetch_user_infos("freyskeyd"),
+        clone.fetch_user_infos("freyskeyd")
+    );
+
+    assert!(first.is_ok() && second.is_ok());
+    assert!(start.elapsed() >= Duration::from_millis(190));
+}
+
+#[tokio::test]
+async fn test_budgets_are_per_endpoint() {
+    let client = client();
+
+    let start = Instant::now();
+
+    for _ in 0..2 {
+        assert!(client.fetch_user_infos("freyskeyd").await.is_ok());
+        assert!(client
+            .fetch_user_feed("1234".parse().unwrap(), None)
+            .await
+            .is_ok());
+    }
+
+    // The fetches of medias have no budget
+    for _ in 0..5 {
+        let _media = client.fetch_media_infos("1".parse().unwrap()).await;
+    }
+
+    assert!(start.elapsed() < Duration::from_millis(100));
+}
+
+/// Profiles failing with a 503 until the last attempt of a request
+#[derive(Clone, Default)]
+struct Unavailable {
+    attempts: Arc<AtomicUsize>,
+}
+
+#[async_trait]
+impl Transport for Unavailable {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        if self.attempts.fetch_add(1, Ordering::SeqCst) < 3 {
+            return Ok(Response {
+                status: StatusCode::SERVICE_UNAVAILABLE,
+                headers: HeaderMap::new(),
+                body: Vec::new(),
+            });
+        }
+
+        Fixtures.send(request).await
+    }
+}
+
+#[tokio::test]
+async fn test_retries_stay_within_budget() {
+    let transport = Unavailable::default();
+    let client = ClientBuilder::new()
+        .set_rate_limits(RateLimits::new().profile(Budget::new(2, Duration::from_millis(200))))
+        .set_retry_policy(
+            RetryPolicy::new()
+                .initial_backoff(Duration::from_millis(1))
+                .jitter(false),
+        )
+        .build()
+        .with_transport(transport.clone());
+
+    let start = Instant::now();
+
+    assert!(client.fetch_user_infos("freyskeyd").await.is_ok());
+
+    // Two attempts at once, then one every 100ms
+    assert_eq!(transport.attempts.load(Ordering::SeqCst), 4);
+    assert!(start.elapsed() >= Duration::from_millis(190));
+}
@@ -0,0 +1,135 @@
+extern crate instagram;
+
+use mockito::mock;
+use mockito::Matcher;
+
+use instagram::web_api::behaviour::*;
+use instagram::web_api::domain::MediaId;
+use instagram::web_api::error::RateLimitKind;
+use instagram::web_api::response::UserInfosError;
+use instagram::web_api::Client;
+use instagram::web_api::ClientError;
+use instagram::web_api::Credentials;
+use std::time::Duration;
+
+#[tokio::test]
+async fn test_too_many_requests() {
+    let m = mock("GET", "/rate_limited/freyskeyd")
+        .match_query(Matcher::UrlEncoded("__a".into(), "1".into()))
+        .with_status(429)
+        .with_header("retry-after", "120")
+        .with_body("{\"message\": \"rate limited\", \"status\": \"fail\"}")
+        .expect(1)
+        .create();
+
+    let client = Client::new_with_url(&format!("{}/rate_limited", mockito::server_url()), "");
+
+    match client.fetch_user_infos("freyskeyd").await {
+        Err(UserInfosError::RateLimited(limited)) => {
+            assert_eq!(limited.kind(), RateLimitKind::TooManyRequests);
+            assert_eq!(limited.message(), "rate limited");
+            assert_eq!(limited.retry_after(), Duration::from_secs(120));
+        }
+        other => panic!("expected a rate limit, got {:?}", other),
+    }
+
+    m.assert();
+}
+
+#[tokio::test]
+async fn test_login_redirect() {
+    let m = mock("GET", "/rate_limited/graphql")
+        .match_query(Matcher::Regex("query_hash=.*".into()))
+        .with_status(302)
+        .with_header("location", "/rate_limited/accounts/login/?next=/graphql/")
+        .expect(1)
+        .create();
+
+    let m_login_page = mock("GET", "/rate_limited/accounts/login/")
+        .match_query(Matcher::Any)
+        .with_status(200)
+        .with_header("content-type", "text/html; charset=utf-8")
+        .with_body(
+            "<!DOCTYPE html>\n<html><script>window._sharedData = {\"entry_data\":{\"LoginAndSignupPage\":[{}]}};</script></html>",
+        )
+        .expect(1)
+        .create();
+
+    let client = Client::new_with_url(
+        "",
+        &format!("{}/rate_limited/graphql", mockito::server_url()),
+    );
+
+    match client.fetch_user_feed("1234".parse().unwrap(), None).await {
+        Err(ClientError::RateLimited(limited)) => {
+            assert_eq!(limited.kind(), RateLimitKind::LoginRedirect);
+        }
+        other => panic!("expected a login redirect, got {:?}", other),
+    }
+
+    m.assert();
+    m_login_page.assert();
+}
+
+#[tokio::test]
+async fn test_login_please_wait() {
+    let fixture_init_rollout_hash: String =
+        ::std::fs::read_to_string("tests/web_api_client/response_init_rollout.html").unwrap();
+
+    let m_root = mock("GET", "/please_wait/")
+        .with_status(200)
+        .with_body(fixture_init_rollout_hash)
+        .expect(1)
+        .create();
+
+    let m_login = mock("POST", "/please_wait/accounts/login/ajax/")
+        .with_status(400)
+        .with_body("{\"message\": \"Please wait a few minutes before you try again.\", \"status\": \"fail\"}")
+        .expect(1)
+        .create();
+
+    let result = Client::new_with_url(&format!("{}/please_wait", mockito::server_url()), "")
+        .login(&Credentials {
+            username: "user",
+            password: "passw",
+        })
+        .await;
+
+    match result {
+        Err(ClientError::RateLimited(limited)) => {
+            assert_eq!(limited.kind(), RateLimitKind::PleaseWait);
+            assert_eq!(
+                limited.message(),
+                "Please wait a few minutes before you try again."
+            );
+        }
+        other => panic!("expected to be asked to wait, got {:?}", other),
+    }
+
+    m_root.assert();
+    m_login.assert();
+}
+
+#[tokio::test]
+async fn test_please_wait_in_successful_response() {
+    let m = mock("GET", Matcher::Regex(r"^/please_wait_ok/p/[^/]+/$".into()))
+        .match_query(Matcher::UrlEncoded("__a".into(), "1".into()))
+        .with_status(200)
+        .with_body("{\"message\": \"Please wait a few minutes before you try again.\", \"status\": \"fail\"}")
+        .expect(1)
+        .create();
+
+    let client = Client::new_with_url(&format!("{}/please_wait_ok", mockito::server_url()), "");
+
+    match client
+        .fetch_media_infos(MediaId::new(2_250_382_848_586_272_543))
+        .await
+    {
+        Err(ClientError::RateLimited(limited)) => {
+            assert_eq!(limited.kind(), RateLimitKind::PleaseWait);
+        }
+        other => panic!("expected to be asked to wait, got {:?}", other),
+    }
+
+    m.assert();
+}
@@ -0,0 +1,177 @@
+extern crate instagram;
+
+use async_trait::async_trait;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+
+use instagram::web_api::behaviour::*;
+use instagram::web_api::retry::RetryPolicy;
+use instagram::web_api::transport::{
+    HeaderMap, Request, Response, StatusCode, Transport, TransportError,
+};
+use instagram::web_api::{ClientBuilder, ClientError, Credentials};
+
+/// Transport answering with the scripted results in order, the last one once the others are used
+///
+/// Successful responses have the fixture as body, the others are empty.
+#[derive(Clone)]
+struct Flaky {
+    fixture: &'static str,
+    results: Arc<Mutex<Vec<Result<StatusCode, TransportError>>>>,
+    attempts: Arc<Mutex<usize>>,
+}
+
+impl Flaky {
+    fn new(results: Vec<Result<StatusCode, TransportError>>) -> Self {
+        Self::with_fixture("tests/web_api_client/response_user_info.json", results)
+    }
+
+    fn with_fixture(
+        fixture: &'static str,
+        results: Vec<Result<StatusCode, TransportError>>,
+    ) -> Self {
+        Self {
+            fixture,
+            results: Arc::new(Mutex::new(results)),
+            attempts: Arc::default(),
+        }
+    }
+
+    fn attempts(&self) -> usize {
+        *self.attempts.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl Transport for Flaky {
+    async fn send(&self, _request: Request) -> Result<Response, TransportError> {
+        *self.attempts.lock().unwrap() += 1;
+
+        let mut results = self.results.lock().unwrap();
+        let result = if results.len() > 1 {
+            results.remove(0)
+        } else {
+            results[0].clone()
+        };
+
+        result.map(|status| Response {
+            status,
+            headers: HeaderMap::new(),
+            body: if status.is_success() {
+                std::fs::read(self.fixture).unwrap()
+            } else {
+                Vec::new()
+            },
+        })
+    }
+}
+
+fn policy() -> RetryPolicy {
+    RetryPolicy::new()
+        .initial_backoff(Duration::from_millis(1))
+        .max_backoff(Duration::from_millis(4))
+}
+
+#[tokio::test]
+async fn test_transient_errors_are_retried() {
+    let transport = Flaky::new(vec![
+        Err(TransportError::Connection("connection reset".to_string())),
+        Ok(StatusCode::SERVICE_UNAVAILABLE),
+        Ok(StatusCode::TOO_MANY_REQUESTS),
+        Ok(StatusCode::OK),
+    ]);
+
+    let client = ClientBuilder::new()
+        .set_retry_policy(policy())
+        .build()
+        .with_transport(transport.clone());
+
+    let user = client.fetch_user_infos("freyskeyd").await.unwrap();
+
+    assert_eq!(user.username, "freyskeyd");
+    assert_eq!(transport.attempts(), 4);
+}
+
+#[tokio::test]
+async fn test_retries_are_bounded() {
+    let transport = Flaky::new(vec![Ok(StatusCode::BAD_GATEWAY)]);
+
+    let client = ClientBuilder::new()
+        .set_retry_policy(policy().max_retries(2))
+        .build()
+        .with_transport(transport.clone());
+
+    assert!(client.fetch_user_infos("freyskeyd").await.is_err());
+    assert_eq!(transport.attempts(), 3);
+
+    let transport = Flaky::new(vec![Ok(StatusCode::BAD_GATEWAY)]);
+
+    let client = ClientBuilder::new()
+        .set_retry_policy(
+            policy()
+                .max_retries(10)
+                .initial_backoff(Duration::from_millis(50))
+                .max_backoff(Duration::from_millis(50))
+                .max_elapsed_time(Duration::from_millis(20)),
+        )
+        .build()
+        .with_transport(transport.clone());
+
+    assert!(client.fetch_user_infos("freyskeyd").await.is_err());
+    assert_eq!(transport.attempts(), 1);
+}
+
+#[tokio::test]
+async fn test_mutations_and_client_errors_are_not_retried() {
+    let credentials = Credentials {
+        username: "user",
+        password: "password",
+    };
+    let results = vec![Ok(StatusCode::OK), Ok(StatusCode::SERVICE_UNAVAILABLE)];
+
+    let transport = Flaky::with_fixture(
+        "tests/web_api_client/response_init_rollout.html",
+        results.clone(),
+    );
+
+    let result = ClientBuilder::new()
+        .set_retry_policy(policy())
+        .build()
+        .with_transport(transport.clone())
+        .login(&credentials)
+        .await;
+
+    assert!(matches!(result, Err(ClientError::Decode(_))));
+    assert_eq!(transport.attempts(), 2);
+
+    let transport = Flaky::with_fixture("tests/web_api_client/response_init_rollout.html", results);
+
+    let result = ClientBuilder::new()
+        .set_retry_policy(policy().retry_mutations(true))
+        .build()
+        .with_transport(transport.clone())
+        .login(&credentials)
+        .await;
+
+    assert!(matches!(result, Err(ClientError::Decode(_))));
+    assert_eq!(transport.attempts(), 5);
+
+    let transport = Flaky::new(vec![Ok(StatusCode::NOT_FOUND)]);
+
+    let client = ClientBuilder::new()
+        .set_retry_policy(policy())
+        .build()
+        .with_transport(transport.clone());
+
+    assert!(client.fetch_user_infos("freyskeyd").await.is_err());
+    assert_eq!(transport.attempts(), 1);
+
+    let transport = Flaky::new(vec![Ok(StatusCode::SERVICE_UNAVAILABLE)]);
+
+    let client = ClientBuilder::new()
+        .build()
+        .with_transport(transport.clone());
+
+    assert!(client.fetch_user_infos("freyskeyd").await.is_err());
+    assert_eq!(transport.attempts(), 1);
+}
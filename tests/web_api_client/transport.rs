@@ -0,0 +1,156 @@
+extern crate instagram;
+
+use async_trait::async_trait;
+use std::io::ErrorKind;
+use std::path::Path;
+use std::sync::{Arc, Mutex};
+
+use instagram::web_api::behaviour::*;
+use instagram::web_api::options::Photo;
+use instagram::web_api::transport::{
+    HeaderMap, Request, Response, StatusCode, Transport, TransportError,
+};
+use instagram::web_api::{AuthenticatedClient, Client, ClientError, Session};
+
+/// Transport answering every request with the same body, keeping the requests it received
+#[derive(Clone, Default)]
+struct InProcess {
+    body: Vec<u8>,
+    requests: Arc<Mutex<Vec<Request>>>,
+}
+
+#[async_trait]
+impl Transport for InProcess {
+    async fn send(&self, request: Request) -> Result<Response, TransportError> {
+        self.requests.lock().unwrap().push(request);
+
+        Ok(Response {
+            status: StatusCode::OK,
+            headers: HeaderMap::new(),
+            body: self.body.clone(),
+        })
+    }
+}
+
+struct Unreachable;
+
+#[async_trait]
+impl Transport for Unreachable {
+    async fn send(&self, _request: Request) -> Result<Response, TransportError> {
+        Err(TransportError::Connection("egress refused".to_string()))
+    }
+}
+
+#[tokio::test]
+async fn test_fetch_through_transport() {
+    let transport = InProcess {
+        body: std::fs::read("tests/web_api_client/response_user_info.json").unwrap(),
+        ..InProcess::default()
+    };
+
+    let user = Client::new_with_url("https://instagram.test", "")
+        .with_transport(transport.clone())
+        .fetch_user_infos("freyskeyd")
+        .await
+        .unwrap();
+
+    assert_eq!(user.username, "freyskeyd");
+
+    let requests = transport.requests.lock().unwrap();
+
+    assert_eq!(requests.len(), 1);
+    assert_eq!(requests[0].method, "GET");
+    assert_eq!(requests[0].url, "https://instagram.test/freyskeyd?__a=1");
+}
+
+#[tokio::test]
+async fn test_authenticated_requests_through_transport() {
+    let transport = InProcess {
+        body: br#"{"status": "ok"}"#.to_vec(),
+        ..InProcess::default()
+    };
+
+    let session = Session {
+        csrf_token: Some("logged_in_token".to_string()),
+        cookies: vec![
+            ("csrftoken".to_string(), "logged_in_token".to_string()),
+            ("sessionid".to_string(), "some_session".to_string()),
+        ],
+        ..Session::default()
+    };
+
+    let client = AuthenticatedClient::from_session(
+        Client::new_with_url("https://instagram.test", "").with_transport(transport.clone()),
+        session,
+    );
+
+    let archiving = client
+        .archive_media("2245000000000000001".parse().unwrap())
+        .await
+        .unwrap();
+
+    assert!(archiving.archived);
+
+    let requests = transport.requests.lock().unwrap();
+
+    assert_eq!(requests[0].method, "POST");
+    assert_eq!(
+        requests[0].url,
+        "https://instagram.test/api/v1/media/2245000000000000001/only_me/"
+    );
+    assert_eq!(requests[0].headers["x-csrftoken"], "logged_in_token");
+    assert_eq!(
+        requests[0].headers["cookie"],
+        "csrftoken=logged_in_token; sessionid=some_session"
+    );
+}
+
+#[tokio::test]
+async fn test_no_cookie_header_without_session_cookies() {
+    let transport = InProcess {
+        body: br#"{"status": "ok"}"#.to_vec(),
+        ..InProcess::default()
+    };
+
+    let client = AuthenticatedClient::from_session(
+        Client::new_with_url("https://instagram.test", "").with_transport(transport.clone()),
+        Session::default(),
+    );
+
+    client
+        .archive_media("2245000000000000001".parse().unwrap())
+        .await
+        .unwrap();
+
+    let requests = transport.requests.lock().unwrap();
+
+    assert!(!requests[0].headers.contains_key("cookie"));
+}
+
+#[tokio::test]
+async fn test_unreadable_photo() {
+    let client = AuthenticatedClient::from_session(
+        Client::new().with_transport(Unreachable),
+        Session::default(),
+    );
+
+    let error = client
+        .post_photo(Photo::Path(Path::new("tests/missing_photo.jpg")), None)
+        .await
+        .unwrap_err();
+
+    match error {
+        ClientError::UnableToReadPhoto(error) => assert_eq!(error.kind(), ErrorKind::NotFound),
+        other => panic!("Unexpected error {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_transport_error() {
+    let feed = Client::new()
+        .with_transport(Unreachable)
+        .fetch_user_feed("8999249".parse().unwrap(), None)
+        .await;
+
+    assert_eq!(feed.unwrap_err(), ClientError::HttpRequest);
+}
@@ -9,9 +9,9 @@ fn behaviour() {
     assert_impl!(FetchUserInfos: Client);
     assert_impl!(FetchUserFeed: Client);
 
+    assert_impl!(FetchMediaInfos: Client);
+    assert_impl!(FetchMediaComments: Client);
     // TODO implementation needed
-    assert_impl!(!FetchMediaInfos: Client);
-    assert_impl!(!FetchMediaComments: Client);
     assert_impl!(!Search: Client);
     assert_impl!(!FetchTagFeed: Client);
     assert_impl!(!FetchLocationFeed: Client);